@@ -0,0 +1,167 @@
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde_json::Value;
+
+use crate::target::Target;
+
+/// What to build, the library of a package or one of its examples.
+#[derive(Clone, Debug)]
+pub enum Artifact {
+    Lib(String),
+    Example(String),
+}
+
+impl Artifact {
+    /// The name of the artifact, as cargo names the output file.
+    pub fn name(&self) -> String {
+        match self {
+            Artifact::Lib(name) | Artifact::Example(name) => name.replace('-', "_"),
+        }
+    }
+}
+
+/// The parts of `cargo metadata` the bundler needs.
+pub struct Metadata {
+    pub target_directory: PathBuf,
+    pub package: Value,
+}
+
+impl Metadata {
+    /// Run `cargo metadata` and select the package to bundle.
+    pub fn load(package: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let output = cargo()
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into());
+        }
+
+        let metadata: Value = serde_json::from_slice(&output.stdout)?;
+
+        let target_directory = metadata["target_directory"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or("cargo metadata has no target directory")?;
+
+        let packages = metadata["packages"]
+            .as_array()
+            .ok_or("cargo metadata has no packages")?;
+
+        let current_dir = env::current_dir()?;
+
+        let package = match package {
+            Some(name) => packages.iter().find(|p| p["name"] == name),
+            None if packages.len() == 1 => packages.first(),
+            None => packages.iter().find(|p| {
+                let manifest = p["manifest_path"].as_str().map(Path::new);
+                manifest.and_then(Path::parent) == Some(current_dir.as_path())
+            }),
+        };
+
+        let package = package
+            .cloned()
+            .ok_or("could not determine the package to bundle, use `--package`")?;
+
+        Ok(Self {
+            target_directory,
+            package,
+        })
+    }
+
+    /// The name of the package.
+    pub fn package_name(&self) -> &str {
+        self.package["name"].as_str().unwrap_or_default()
+    }
+
+    /// The version of the package.
+    pub fn package_version(&self) -> &str {
+        self.package["version"].as_str().unwrap_or_default()
+    }
+
+    /// The name of the `cdylib` target of the package.
+    pub fn lib_name(&self) -> Option<String> {
+        let targets = self.package["targets"].as_array()?;
+
+        targets.iter().find_map(|target| {
+            let kinds = target["kind"].as_array()?;
+            let is_cdylib = kinds.iter().any(|kind| kind == "cdylib");
+
+            match is_cdylib {
+                true => target["name"].as_str().map(String::from),
+                false => None,
+            }
+        })
+    }
+
+    /// The bundle name configured in `[package.metadata.vst3]`.
+    ///
+    /// Examples are configured in `[package.metadata.vst3.examples.<name>]`.
+    pub fn bundle_name(&self, artifact: &Artifact) -> Option<String> {
+        let vst3 = &self.package["metadata"]["vst3"];
+
+        let name = match artifact {
+            Artifact::Lib(_) => &vst3["name"],
+            Artifact::Example(example) => &vst3["examples"][example]["name"],
+        };
+
+        name.as_str().map(String::from)
+    }
+
+    /// The path cargo writes the built library to.
+    pub fn library_path(&self, artifact: &Artifact, target: &Target, release: bool) -> PathBuf {
+        let mut path = self.target_directory.clone();
+
+        if let Some(ref triple) = target.triple {
+            path.push(triple);
+        }
+
+        path.push(if release { "release" } else { "debug" });
+
+        if let Artifact::Example(_) = artifact {
+            path.push("examples");
+        }
+
+        path.push(target.library_file(&artifact.name()));
+        path
+    }
+}
+
+/// Build `artifact` of `package` for `target`.
+pub fn build(
+    package: &str,
+    artifact: &Artifact,
+    target: &Target,
+    release: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut command = cargo();
+    command.args(["build", "--package", package]);
+
+    match artifact {
+        Artifact::Lib(_) => command.arg("--lib"),
+        Artifact::Example(example) => command.args(["--example", example]),
+    };
+
+    if release {
+        command.arg("--release");
+    }
+
+    if let Some(ref triple) = target.triple {
+        command.args(["--target", triple]);
+    }
+
+    if !command.status()?.success() {
+        return Err("cargo build failed".into());
+    }
+
+    Ok(())
+}
+
+fn cargo() -> Command {
+    Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
@@ -0,0 +1,303 @@
+//! Build a plugin crate and package it as a `.vst3` bundle.
+//!
+//! Run as `cargo vst3-bundle` when installed, or `cargo run -p ori-vst-bundle --` in this
+//! workspace.
+
+use std::{
+    env,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+mod cargo;
+mod module_info;
+mod target;
+
+use cargo::{Artifact, Metadata};
+use target::{Os, Target};
+
+const USAGE: &str = "\
+usage: cargo vst3-bundle [options]
+
+options:
+    -p, --package <name>     package to bundle
+    --example <name>         bundle an example instead of the library
+    --release                build in release mode
+    --target <triple>        build for a target, may be given multiple times
+    --name <name>            name of the bundle
+    --out <dir>              directory to write the bundle to (default: target/bundle)
+    --install                install the bundle to the user VST3 directory";
+
+struct Args {
+    package: Option<String>,
+    example: Option<String>,
+    release: bool,
+    targets: Vec<String>,
+    name: Option<String>,
+    out: Option<PathBuf>,
+    install: bool,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let Some(args) = parse_args()? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let metadata = Metadata::load(args.package.as_deref())?;
+
+    let artifact = match args.example {
+        Some(ref example) => Artifact::Example(example.clone()),
+        None => {
+            let lib = metadata
+                .lib_name()
+                .ok_or("package has no `cdylib` library, use `--example`")?;
+
+            Artifact::Lib(lib)
+        }
+    };
+
+    let name = args
+        .name
+        .clone()
+        .or_else(|| metadata.bundle_name(&artifact))
+        .unwrap_or_else(|| artifact.name());
+
+    let targets = match args.targets.is_empty() {
+        true => vec![Target::host()],
+        false => args
+            .targets
+            .iter()
+            .map(|triple| Target::parse(triple))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    for target in &targets {
+        cargo::build(metadata.package_name(), &artifact, target, args.release)?;
+    }
+
+    let out = match args.out {
+        Some(ref out) => out.clone(),
+        None => metadata.target_directory.join("bundle"),
+    };
+    let bundle = out.join(format!("{}.vst3", name));
+
+    if bundle.exists() {
+        fs::remove_dir_all(&bundle)?;
+    }
+
+    let contents = bundle.join("Contents");
+    let mut host_binary = None;
+
+    for target in &targets {
+        let library = metadata.library_path(&artifact, target, args.release);
+
+        let folder = contents.join(target.bundle_folder());
+        let binary = folder.join(target.bundle_binary(&name));
+
+        fs::create_dir_all(&folder)?;
+
+        if target.os == Os::MacOs && binary.exists() {
+            lipo(&binary, &library)?;
+        } else {
+            fs::copy(&library, &binary)?;
+        }
+
+        if target.os == Os::Linux && target.is_host() {
+            patch_rpath(&binary)?;
+        }
+
+        if target.is_host() {
+            host_binary = Some(binary);
+        }
+    }
+
+    if targets.iter().any(|target| target.os == Os::MacOs) {
+        write_info_plist(&contents, &name, metadata.package_version())?;
+    }
+
+    match host_binary {
+        Some(ref binary) => {
+            let info = module_info::read(binary, &name, metadata.package_version())?;
+
+            let resources = contents.join("Resources");
+            fs::create_dir_all(&resources)?;
+            fs::write(resources.join("moduleinfo.json"), info.to_json())?;
+        }
+        None => {
+            eprintln!("warning: no host binary in bundle, skipping moduleinfo.json");
+        }
+    }
+
+    println!("bundled {}", bundle.display());
+
+    if args.install {
+        let vst3 = user_vst3_dir()?;
+        let installed = vst3.join(bundle.file_name().unwrap());
+
+        if installed.exists() {
+            fs::remove_dir_all(&installed)?;
+        }
+
+        copy_dir(&bundle, &installed)?;
+
+        println!("installed {}", installed.display());
+    }
+
+    Ok(())
+}
+
+fn parse_args() -> Result<Option<Args>, Box<dyn Error>> {
+    let mut args = env::args().skip(1).peekable();
+
+    // when invoked as `cargo vst3-bundle`, cargo passes the subcommand name first
+    if args.peek().map(String::as_str) == Some("vst3-bundle") {
+        args.next();
+    }
+
+    let mut parsed = Args {
+        package: None,
+        example: None,
+        release: false,
+        targets: Vec::new(),
+        name: None,
+        out: None,
+        install: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{}`", arg))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-p" | "--package" => parsed.package = Some(value()?),
+            "--example" => parsed.example = Some(value()?),
+            "--release" => parsed.release = true,
+            "--target" => parsed.targets.push(value()?),
+            "--name" => parsed.name = Some(value()?),
+            "--out" => parsed.out = Some(PathBuf::from(value()?)),
+            "--install" => parsed.install = true,
+            _ => return Err(format!("unknown argument `{}`\n\n{}", arg, USAGE).into()),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+/// Add the directories in `LD_LIBRARY_PATH` to the rpath of `binary`.
+///
+/// This lets hosts find libraries that are only available in the build environment,
+/// eg. in a nix shell. Nothing is done if `patchelf` isn't installed.
+fn patch_rpath(binary: &Path) -> Result<(), Box<dyn Error>> {
+    let Some(paths) = env::var_os("LD_LIBRARY_PATH") else {
+        return Ok(());
+    };
+
+    let status = Command::new("patchelf")
+        .arg("--add-rpath")
+        .arg(paths)
+        .arg(binary)
+        .status();
+
+    match status {
+        Ok(status) if !status.success() => Err("patchelf failed".into()),
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Merge `library` into the universal binary at `binary`.
+fn lipo(binary: &Path, library: &Path) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("lipo")
+        .arg("-create")
+        .arg(binary)
+        .arg(library)
+        .arg("-output")
+        .arg(binary)
+        .status()?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err("lipo failed".into()),
+    }
+}
+
+fn write_info_plist(contents: &Path, name: &str, version: &str) -> Result<(), Box<dyn Error>> {
+    let identifier: String = name.chars().filter(char::is_ascii_alphanumeric).collect();
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleExecutable</key>
+    <string>{name}</string>
+    <key>CFBundleIdentifier</key>
+    <string>com.ori-vst.{identifier}</string>
+    <key>CFBundleName</key>
+    <string>{name}</string>
+    <key>CFBundlePackageType</key>
+    <string>BNDL</string>
+    <key>CFBundleSignature</key>
+    <string>????</string>
+    <key>CFBundleShortVersionString</key>
+    <string>{version}</string>
+    <key>CFBundleVersion</key>
+    <string>{version}</string>
+</dict>
+</plist>
+"#
+    );
+
+    fs::write(contents.join("Info.plist"), plist)?;
+    fs::write(contents.join("PkgInfo"), "BNDL????")?;
+
+    Ok(())
+}
+
+fn user_vst3_dir() -> Result<PathBuf, Box<dyn Error>> {
+    if cfg!(target_os = "windows") {
+        let local = env::var_os("LOCALAPPDATA").ok_or("LOCALAPPDATA is not set")?;
+        return Ok(PathBuf::from(local).join("Programs/Common/VST3"));
+    }
+
+    let home = env::var_os("HOME").ok_or("HOME is not set")?;
+
+    match cfg!(target_os = "macos") {
+        true => Ok(PathBuf::from(home).join("Library/Audio/Plug-Ins/VST3")),
+        false => Ok(PathBuf::from(home).join(".vst3")),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = to.join(entry.file_name());
+
+        match entry.file_type()?.is_dir() {
+            true => copy_dir(&entry.path(), &path)?,
+            false => {
+                fs::copy(entry.path(), path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, path::Path, ptr::NonNull};
+
+use ori_vst_module_info::{Module, ModuleInfo};
+use vst3_com::VstPtr;
+use vst3_sys::base::IPluginFactory;
+
+/// Load the plugin library at `path` and read its module information.
+///
+/// The metadata is read from the plugin factory, so it is exactly what hosts see at runtime.
+pub fn read(path: &Path, name: &str, version: &str) -> Result<ModuleInfo, Box<dyn Error>> {
+    unsafe {
+        let module = Module::load(path)?;
+        let factory = NonNull::new(module.factory()?).ok_or("plugin did not return a factory")?;
+        let factory = VstPtr::<dyn IPluginFactory>::owned(factory.cast());
+
+        Ok(ModuleInfo::from_factory(&factory, name, version))
+    }
+}
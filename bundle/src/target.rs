@@ -0,0 +1,97 @@
+use std::env::consts;
+
+/// The operating system of a target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Os {
+    Linux,
+    Windows,
+    MacOs,
+}
+
+/// A build target, and where its binary goes in a `.vst3` bundle.
+#[derive(Clone, Debug)]
+pub struct Target {
+    /// The target triple, `None` for the host.
+    pub triple: Option<String>,
+    pub arch: String,
+    pub os: Os,
+}
+
+impl Target {
+    /// The target the bundler itself is running on.
+    pub fn host() -> Self {
+        let os = match consts::OS {
+            "windows" => Os::Windows,
+            "macos" => Os::MacOs,
+            _ => Os::Linux,
+        };
+
+        Self {
+            triple: None,
+            arch: String::from(consts::ARCH),
+            os,
+        }
+    }
+
+    /// Parse a target triple, eg. `x86_64-unknown-linux-gnu`.
+    pub fn parse(triple: &str) -> Result<Self, String> {
+        let arch = triple.split('-').next().unwrap_or_default();
+
+        let os = if triple.contains("-linux") {
+            Os::Linux
+        } else if triple.contains("-windows") {
+            Os::Windows
+        } else if triple.contains("-apple-darwin") {
+            Os::MacOs
+        } else {
+            return Err(format!("unsupported target `{}`", triple));
+        };
+
+        Ok(Self {
+            triple: Some(String::from(triple)),
+            arch: String::from(arch),
+            os,
+        })
+    }
+
+    /// Check whether binaries for this target can be loaded by the bundler.
+    pub fn is_host(&self) -> bool {
+        let host = Self::host();
+        self.arch == host.arch && self.os == host.os
+    }
+
+    /// The directory in `Contents` that holds the binary for this target.
+    pub fn bundle_folder(&self) -> String {
+        let arch = match (self.os, self.arch.as_str()) {
+            (_, "x86" | "i586" | "i686") if self.os == Os::Windows => "x86",
+            (_, "x86" | "i586" | "i686") => "i386",
+            (Os::Windows, "aarch64") => "arm64",
+            (Os::Linux, "armv7") => "armv7l",
+            (_, arch) => arch,
+        };
+
+        match self.os {
+            Os::Linux => format!("{}-linux", arch),
+            Os::Windows => format!("{}-win", arch),
+            Os::MacOs => String::from("MacOS"),
+        }
+    }
+
+    /// The file name cargo gives the library `name` on this target.
+    pub fn library_file(&self, name: &str) -> String {
+        match self.os {
+            Os::Linux => format!("lib{}.so", name),
+            Os::Windows => format!("{}.dll", name),
+            Os::MacOs => format!("lib{}.dylib", name),
+        }
+    }
+
+    /// The file name of the plugin binary inside the bundle.
+    pub fn bundle_binary(&self, name: &str) -> String {
+        match self.os {
+            Os::Linux => format!("{}.so", name),
+            Os::Windows => format!("{}.vst3", name),
+            Os::MacOs => String::from(name),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use ori_vst::prelude::*;
 
 #[derive(Params)]
+#[params(default)]
 pub struct GainPlugin {
-    #[param(name = "Gain", unit = Unit::Decibels)]
+    #[param(name = "Gain", unit = Unit::Decibels, range = 0.0..=20.0, default = 1.0, automate)]
     gain: Float,
 }
 
@@ -28,9 +29,7 @@ impl VstPlugin for GainPlugin {
     }
 
     fn new() -> Self {
-        Self {
-            gain: Float::new(1.0, 0.0..=20.0),
-        }
+        Self::default()
     }
 
     fn params(&mut self) -> &mut dyn Params {
@@ -0,0 +1,12 @@
+//! Run the editor of the gain example in a standalone window.
+//!
+//! ```sh
+//! cargo run --example gain_preview
+//! ```
+
+#[path = "gain.rs"]
+mod gain;
+
+fn main() {
+    ori_vst::Preview::new().run::<gain::GainPlugin>();
+}
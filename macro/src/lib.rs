@@ -12,7 +12,15 @@ fn find_ori_vst() -> syn::Path {
     }
 }
 
-#[proc_macro_derive(Params, attributes(param))]
+/// Derive `Params` for a struct of parameters.
+///
+/// `#[param(id = "...")]` sets the key of the parameter in the saved state. Hosts address
+/// parameters by their index, so reordering the fields still changes which parameter automation
+/// applies to.
+///
+/// The `range` and `default` attributes require `#[params(default)]` on the struct, which
+/// implements `Default` with them.
+#[proc_macro_derive(Params, attributes(param, params))]
 pub fn derive_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     params::derive_params(input)
 }
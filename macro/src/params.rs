@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{parse::ParseStream, Token};
+use syn::{ext::IdentExt, parse::ParseStream, Token};
 
 use crate::find_ori_vst;
 
@@ -9,6 +9,13 @@ syn::custom_keyword!(name);
 syn::custom_keyword!(short);
 syn::custom_keyword!(unit);
 syn::custom_keyword!(steps);
+syn::custom_keyword!(range);
+syn::custom_keyword!(default);
+syn::custom_keyword!(id);
+syn::custom_keyword!(automate);
+syn::custom_keyword!(hidden);
+syn::custom_keyword!(read_only);
+syn::custom_keyword!(bypass);
 
 #[derive(Default)]
 struct Attributes {
@@ -17,6 +24,10 @@ struct Attributes {
     short: Option<String>,
     unit: Option<syn::Expr>,
     steps: Option<syn::Expr>,
+    range: Option<syn::Expr>,
+    default: Option<syn::Expr>,
+    id: Option<String>,
+    flags: Vec<syn::Ident>,
 }
 
 impl Attributes {
@@ -26,6 +37,10 @@ impl Attributes {
         let mut short = None;
         let mut unit = None;
         let mut steps = None;
+        let mut range = None;
+        let mut default = None;
+        let mut id = None;
+        let mut flags = Vec::<syn::Ident>::new();
 
         for attr in attrs {
             if attr.path().is_ident("param") {
@@ -106,8 +121,70 @@ impl Attributes {
                             }
 
                             steps = Some(steps_value);
+                        } else if input.parse::<range>().is_ok() {
+                            input.parse::<Token![=]>()?;
+                            let range_value = input.parse::<syn::Expr>()?;
+
+                            if range.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    range_value,
+                                    "duplicate range attribute",
+                                ));
+                            }
+
+                            range = Some(range_value);
+                        } else if input.parse::<default>().is_ok() {
+                            input.parse::<Token![=]>()?;
+                            let default_value = input.parse::<syn::Expr>()?;
+
+                            if default.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    default_value,
+                                    "duplicate default attribute",
+                                ));
+                            }
+
+                            default = Some(default_value);
+                        } else if input.parse::<id>().is_ok() {
+                            input.parse::<Token![=]>()?;
+                            let id_value = input.parse::<syn::LitStr>()?;
+
+                            if id.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    id_value,
+                                    "duplicate id attribute",
+                                ));
+                            }
+
+                            if id_value.value().is_empty() {
+                                return Err(syn::Error::new_spanned(
+                                    id_value,
+                                    "id value cannot be empty",
+                                ));
+                            }
+
+                            id = Some(id_value.value());
+                        } else if input.peek(automate)
+                            || input.peek(hidden)
+                            || input.peek(read_only)
+                            || input.peek(bypass)
+                        {
+                            let flag = input.call(syn::Ident::parse_any)?;
+
+                            if flags.contains(&flag) {
+                                return Err(syn::Error::new_spanned(
+                                    &flag,
+                                    format!("duplicate {} attribute", flag),
+                                ));
+                            }
+
+                            flags.push(flag);
                         } else {
-                            return Err(input.error("expected `group`"));
+                            return Err(input.error(
+                                "expected one of `group`, `name`, `short`, `unit`, `steps`, \
+                                 `range`, `default`, `id`, `automate`, `hidden`, `read_only` \
+                                 or `bypass`",
+                            ));
                         }
 
                         if input.is_empty() {
@@ -122,11 +199,19 @@ impl Attributes {
             }
         }
 
-        let is_param = name.is_some() || short.is_some() || unit.is_some() || steps.is_some();
+        let is_param = name.is_some()
+            || short.is_some()
+            || unit.is_some()
+            || steps.is_some()
+            || range.is_some()
+            || default.is_some()
+            || id.is_some()
+            || !flags.is_empty();
+
         if group && is_param {
             return Err(syn::Error::new_spanned(
                 name,
-                "group attribute cannot be used with name, short, or unit attributes",
+                "group attribute cannot be used with parameter attributes",
             ));
         }
 
@@ -136,10 +221,41 @@ impl Attributes {
             short,
             unit,
             steps,
+            range,
+            default,
+            id,
+            flags,
         })
     }
 }
 
+/// Whether `#[params(default)]` asks for an implementation of `Default`.
+fn derive_default(attrs: &[syn::Attribute]) -> Result<bool, syn::Error> {
+    let mut derive = false;
+
+    for attr in attrs {
+        if !attr.path().is_ident("params") {
+            continue;
+        }
+
+        attr.parse_args_with(|input: ParseStream| {
+            let keyword = input.parse::<default>()?;
+
+            if derive {
+                return Err(syn::Error::new_spanned(
+                    keyword,
+                    "duplicate default attribute",
+                ));
+            }
+
+            derive = true;
+            Ok(())
+        })?;
+    }
+
+    Ok(derive)
+}
+
 pub fn derive_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
 
@@ -152,9 +268,26 @@ pub fn derive_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     let info = info(&fields);
     let param = param(&fields);
     let identifier = identifier(&fields);
+    let take_changed = take_changed(&fields);
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
+    let default = match derive_default(&input.attrs) {
+        Ok(true) => {
+            let construct = construct(&fields);
+
+            quote! {
+                impl #impl_generics ::std::default::Default for #ident #ty_generics #where_clause {
+                    fn default() -> Self {
+                        #construct
+                    }
+                }
+            }
+        }
+        Ok(false) => unused_defaults(&fields),
+        Err(err) => err.to_compile_error(),
+    };
+
     let expanded = quote! {
         impl #impl_generics #ori_vst::Params for #ident #ty_generics #where_clause {
             fn count(&self) -> ::std::primitive::usize {
@@ -181,7 +314,14 @@ pub fn derive_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             ) -> ::std::option::Option<::std::string::String> {
                 #identifier
             }
+
+            #[allow(unused_assignments, unused_variables)]
+            fn take_changed(&mut self, f: &mut dyn ::std::ops::FnMut(::std::primitive::usize)) {
+                #take_changed
+            }
         }
+
+        #default
     };
 
     expanded.into()
@@ -261,6 +401,11 @@ fn info(fields: &[syn::Field]) -> TokenStream {
                     None => quote! { #ori_vst::Param::steps(&self.#ident).unwrap_or(0) },
                 };
 
+                let flags = attrs.flags.iter().map(|flag| {
+                    let flag = syn::Ident::new(&flag.to_string().to_uppercase(), flag.span());
+                    quote! { | #ori_vst::ParamFlags::#flag }
+                });
+
                 Ok(quote! {
                     if index == __count {
                         return ::std::option::Option::Some(#ori_vst::ParamInfo {
@@ -269,7 +414,7 @@ fn info(fields: &[syn::Field]) -> TokenStream {
                             unit: #unit,
                             step_count: #steps,
                             default_normalized: #ori_vst::Param::default_normalized(&self.#ident),
-                            flags: #ori_vst::Param::flags(&self.#ident),
+                            flags: #ori_vst::Param::flags(&self.#ident) #(#flags)*,
                         });
                     } else {
                         __count += 1;
@@ -356,11 +501,14 @@ fn identifier(fields: &[syn::Field]) -> TokenStream {
                     }
                 })
             } else {
+                let id = match &attrs.id {
+                    Some(id) => quote! { #id },
+                    None => quote! { ::std::stringify!(#ident) },
+                };
+
                 Ok(quote! {
                     if index == __count {
-                        return ::std::option::Option::Some(
-                            ::std::string::String::from(::std::stringify!(#ident)),
-                        );
+                        return ::std::option::Option::Some(::std::string::String::from(#id));
                     } else {
                         __count += 1;
                     }
@@ -381,6 +529,102 @@ fn identifier(fields: &[syn::Field]) -> TokenStream {
     }
 }
 
+fn take_changed(fields: &[syn::Field]) -> TokenStream {
+    let ori_vst = find_ori_vst();
+
+    let fields = fields
+        .iter()
+        .map(|field| -> Result<TokenStream, syn::Error> {
+            let ident = &field.ident;
+            let attrs = Attributes::new(&field.attrs)?;
+
+            if attrs.group {
+                Ok(quote! {
+                    #ori_vst::Params::take_changed(&mut self.#ident, &mut |index| {
+                        f(__count + index)
+                    });
+
+                    __count += #ori_vst::Params::count(&self.#ident);
+                })
+            } else {
+                Ok(quote! {
+                    if #ori_vst::Param::take_changed(&mut self.#ident) {
+                        f(__count);
+                    }
+
+                    __count += 1;
+                })
+            }
+        })
+        .map(|result| match result {
+            Ok(value) => value,
+            Err(err) => err.to_compile_error(),
+        });
+
+    quote! {
+        let mut __count = 0;
+
+        #(#fields)*
+    }
+}
+
+fn construct(fields: &[syn::Field]) -> TokenStream {
+    let ori_vst = find_ori_vst();
+
+    let fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+
+        let attrs = match Attributes::new(&field.attrs) {
+            Ok(attrs) => attrs,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        if attrs.group {
+            return quote! {
+                #ident: ::std::default::Default::default()
+            };
+        }
+
+        let default = match &attrs.default {
+            Some(default) => quote! { ::std::option::Option::Some(#default) },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        let range = match &attrs.range {
+            Some(range) => quote! { ::std::option::Option::Some(#range) },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        quote! {
+            #ident: <#ty as #ori_vst::DefaultParam>::default_param(#default, #range)
+        }
+    });
+
+    quote! {
+        Self {
+            #(#fields,)*
+        }
+    }
+}
+
+/// Report the `range` and `default` attributes, which only `#[params(default)]` uses.
+fn unused_defaults(fields: &[syn::Field]) -> TokenStream {
+    // invalid attributes are already reported by the `Params` implementation
+    let errors = fields
+        .iter()
+        .filter_map(|field| Attributes::new(&field.attrs).ok())
+        .flat_map(|attrs| [("range", attrs.range), ("default", attrs.default)])
+        .filter_map(|(name, value)| {
+            let message = format!("`{}` requires `#[params(default)]` on the struct", name);
+            Some(syn::Error::new_spanned(value?, message).to_compile_error())
+        });
+
+    quote! {
+        #(#errors)*
+    }
+}
+
 fn get_fields(input: &syn::DeriveInput) -> Vec<syn::Field> {
     match &input.data {
         syn::Data::Struct(data) => match &data.fields {
@@ -0,0 +1,230 @@
+#![warn(missing_docs)]
+
+//! # Ori VST Module Info
+//! The module information of VST3 plugins, as written to `moduleinfo.json`.
+//!
+//! This is shared by ori-vst and its bundler, which reads it from a built plugin without
+//! depending on the framework. The `load` feature adds `Module`, which loads plugin libraries
+//! for the bundler and the offline renderer.
+
+#[cfg(feature = "load")]
+mod module;
+
+use std::{
+    ffi::{c_char, CStr},
+    mem,
+};
+
+use vst3_com::VstPtr;
+use vst3_sys::base::{
+    kResultOk, FactoryFlags, IPluginFactory, IPluginFactory2, PClassInfo, PClassInfo2, PFactoryInfo,
+};
+
+#[cfg(feature = "load")]
+pub use module::Module;
+
+/// The category of audio plugin classes.
+pub const AUDIO_MODULE_CLASS: &str = "Audio Module Class";
+
+/// The VST3 SDK version reported by plugin classes.
+pub const SDK_VERSION: &str = "VST3 3.6.14";
+
+/// Information about a plugin module, as written to `Contents/Resources/moduleinfo.json`.
+///
+/// Hosts read `moduleinfo.json` to scan plugins without loading them, so it must match what
+/// the plugin factory reports at runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleInfo {
+    /// The name of the module.
+    pub name: String,
+
+    /// The version of the module.
+    pub version: String,
+
+    /// The factory information.
+    pub factory: FactoryInfo,
+
+    /// The classes exported by the module.
+    pub classes: Vec<ClassInfo>,
+}
+
+/// Information about a plugin factory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FactoryInfo {
+    /// The vendor of the factory.
+    pub vendor: String,
+
+    /// The URL of the vendor.
+    pub url: String,
+
+    /// The email of the vendor.
+    pub email: String,
+
+    /// The factory flags.
+    pub flags: i32,
+}
+
+/// Information about a class exported by a plugin factory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassInfo {
+    /// The class identifier, in the byte order the factory reports it.
+    pub cid: [u8; 16],
+
+    /// The category of the class.
+    pub category: String,
+
+    /// The name of the class.
+    pub name: String,
+
+    /// The vendor of the class.
+    pub vendor: String,
+
+    /// The version of the class.
+    pub version: String,
+
+    /// The VST3 SDK version the class was built with.
+    pub sdk_version: String,
+
+    /// The subcategories of the class.
+    pub subcategories: Vec<String>,
+
+    /// The class flags.
+    pub class_flags: u32,
+
+    /// The cardinality of the class.
+    pub cardinality: i32,
+}
+
+impl ClassInfo {
+    /// Get the subcategories joined the way the factory reports them.
+    pub fn subcategories_string(&self) -> String {
+        self.subcategories.join("|")
+    }
+}
+
+impl ModuleInfo {
+    /// Read the module information from a plugin factory at runtime.
+    ///
+    /// # Safety
+    /// - `factory` must be a valid plugin factory.
+    pub unsafe fn from_factory(
+        factory: &VstPtr<dyn IPluginFactory>,
+        name: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        let mut factory_info = mem::zeroed::<PFactoryInfo>();
+        factory.get_factory_info(&mut factory_info);
+
+        let factory2 = factory.cast::<dyn IPluginFactory2>();
+        let mut classes = Vec::new();
+
+        for index in 0..factory.count_classes() {
+            if let Some(ref factory2) = factory2 {
+                let mut info = mem::zeroed::<PClassInfo2>();
+
+                if factory2.get_class_info2(index, &mut info) == kResultOk {
+                    let subcategories = string(&info.subcategories);
+
+                    classes.push(ClassInfo {
+                        cid: info.cid.data,
+                        category: string(&info.category),
+                        name: string(&info.name),
+                        vendor: string(&info.vendor),
+                        version: string(&info.version),
+                        sdk_version: string(&info.sdk_version),
+                        subcategories: subcategories
+                            .split('|')
+                            .filter(|subcategory| !subcategory.is_empty())
+                            .map(String::from)
+                            .collect(),
+                        class_flags: info.class_flags,
+                        cardinality: info.cardinality,
+                    });
+
+                    continue;
+                }
+            }
+
+            let mut info = mem::zeroed::<PClassInfo>();
+
+            if factory.get_class_info(index, &mut info) == kResultOk {
+                classes.push(ClassInfo {
+                    cid: info.cid.data,
+                    category: string(&info.category),
+                    name: string(&info.name),
+                    vendor: String::new(),
+                    version: String::new(),
+                    sdk_version: String::new(),
+                    subcategories: Vec::new(),
+                    class_flags: 0,
+                    cardinality: info.cardinality,
+                });
+            }
+        }
+
+        Self {
+            name: name.into(),
+            version: version.into(),
+            factory: FactoryInfo {
+                vendor: string(&factory_info.vendor),
+                url: string(&factory_info.url),
+                email: string(&factory_info.email),
+                flags: factory_info.flags,
+            },
+            classes,
+        }
+    }
+
+    /// Serialize the module information to `moduleinfo.json`.
+    pub fn to_json(&self) -> String {
+        let flags = self.factory.flags;
+
+        let classes = self
+            .classes
+            .iter()
+            .map(|class| {
+                serde_json::json!({
+                    "CID": class.cid.iter().map(|b| format!("{:02X}", b)).collect::<String>(),
+                    "Category": class.category,
+                    "Name": class.name,
+                    "Vendor": class.vendor,
+                    "Version": class.version,
+                    "SDKVersion": class.sdk_version,
+                    "Sub Categories": class.subcategories,
+                    "Class Flags": class.class_flags,
+                    "Cardinality": class.cardinality,
+                    "Snapshots": [],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let json = serde_json::json!({
+            "Name": self.name,
+            "Version": self.version,
+            "Factory Info": {
+                "Vendor": self.factory.vendor,
+                "URL": self.factory.url,
+                "E-Mail": self.factory.email,
+                "Flags": {
+                    "Unicode": flags & FactoryFlags::kUnicode as i32 != 0,
+                    "Classes Discardable": flags & FactoryFlags::kClassesDiscardable as i32 != 0,
+                    "Component Non Discardable":
+                        flags & FactoryFlags::kComponentNonDiscardable as i32 != 0,
+                },
+            },
+            "Compatibility": [],
+            "Classes": classes,
+        });
+
+        serde_json::to_string_pretty(&json).unwrap()
+    }
+}
+
+unsafe fn string(chars: &[c_char]) -> String {
+    match chars.contains(&0) {
+        true => CStr::from_ptr(chars.as_ptr())
+            .to_string_lossy()
+            .into_owned(),
+        false => String::from_utf8_lossy(&*(chars as *const [c_char] as *const [u8])).into_owned(),
+    }
+}
@@ -0,0 +1,67 @@
+use std::{ffi::c_void, path::Path};
+
+use libloading::Library;
+
+type GetPluginFactory = unsafe extern "system" fn() -> *mut c_void;
+
+/// A loaded plugin library.
+///
+/// The module entry point is called when loading, and the exit point when dropped.
+pub struct Module {
+    library: Library,
+}
+
+impl Module {
+    /// Load the plugin library at `path`.
+    ///
+    /// # Safety
+    /// - Loading the library runs its initialization code, see [`Library::new`].
+    pub unsafe fn load(path: &Path) -> Result<Self, libloading::Error> {
+        let library = Library::new(path)?;
+
+        #[cfg(target_os = "linux")]
+        if let Ok(entry) =
+            library.get::<unsafe extern "system" fn(*mut c_void) -> bool>(b"ModuleEntry")
+        {
+            entry(std::ptr::null_mut());
+        }
+
+        #[cfg(target_os = "windows")]
+        if let Ok(entry) = library.get::<unsafe extern "system" fn() -> bool>(b"InitDll") {
+            entry();
+        }
+
+        Ok(Self { library })
+    }
+
+    /// Get the plugin factory of the module, which may be null.
+    ///
+    /// # Safety
+    /// - The factory must not outlive the module.
+    pub unsafe fn factory(&self) -> Result<*mut c_void, libloading::Error> {
+        let get_factory = self.library.get::<GetPluginFactory>(b"GetPluginFactory")?;
+        Ok(get_factory())
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        unsafe {
+            #[cfg(target_os = "linux")]
+            if let Ok(exit) = self
+                .library
+                .get::<unsafe extern "system" fn() -> bool>(b"ModuleExit")
+            {
+                exit();
+            }
+
+            #[cfg(target_os = "windows")]
+            if let Ok(exit) = self
+                .library
+                .get::<unsafe extern "system" fn() -> bool>(b"ExitDll")
+            {
+                exit();
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::Error;
+
+/// Planar audio data.
+#[derive(Clone, Debug, Default)]
+pub struct Audio {
+    /// The sample rate of the audio.
+    pub sample_rate: u32,
+
+    /// The samples of each channel.
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl Audio {
+    /// Create silent audio with `channels` channels of `samples` samples each.
+    pub fn new(channels: usize, samples: usize, sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            channels: vec![vec![0.0; samples]; channels],
+        }
+    }
+
+    /// Read audio from a WAV file.
+    pub fn read_wav(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut reader = WavReader::open(path)?;
+        let spec = reader.spec();
+
+        let samples: Vec<f32> = match spec.sample_format {
+            SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+            SampleFormat::Int => {
+                let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 / scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        let channels = spec.channels as usize;
+        let mut audio = Self::new(channels, samples.len() / channels, spec.sample_rate);
+
+        for (i, frame) in samples.chunks_exact(channels).enumerate() {
+            for (channel, sample) in frame.iter().enumerate() {
+                audio.channels[channel][i] = *sample;
+            }
+        }
+
+        Ok(audio)
+    }
+
+    /// Write the audio to a 32-bit float WAV file.
+    pub fn write_wav(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let spec = WavSpec {
+            channels: self.channels() as u16,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+
+        let mut writer = WavWriter::create(path, spec)?;
+
+        for i in 0..self.samples() {
+            for channel in &self.channels {
+                writer.write_sample(channel[i])?;
+            }
+        }
+
+        writer.finalize()?;
+
+        Ok(())
+    }
+
+    /// Get the number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Get the number of samples per channel.
+    pub fn samples(&self) -> usize {
+        self.channels.first().map_or(0, Vec::len)
+    }
+}
@@ -0,0 +1,81 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::Error;
+
+/// A point in an automation lane.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Point {
+    /// The time of the point in seconds.
+    pub time: f64,
+
+    /// The normalized value of the parameter.
+    pub value: f64,
+}
+
+/// Parameter automation for an offline render.
+///
+/// In JSON this is an object mapping parameters to lists of points, where a parameter is
+/// either its id or its title.
+///
+/// ```json
+/// {
+///     "Gain": [{ "time": 0.0, "value": 0.0 }, { "time": 2.0, "value": 1.0 }],
+///     "3": [{ "time": 0.0, "value": 0.5 }]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Automation {
+    /// The automation lanes, keyed by parameter id or title.
+    pub lanes: BTreeMap<String, Vec<Point>>,
+}
+
+impl Automation {
+    /// Create empty automation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse automation from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let mut automation: Self = serde_json::from_str(json)?;
+
+        for points in automation.lanes.values_mut() {
+            points.sort_by(|a, b| a.time.total_cmp(&b.time));
+        }
+
+        Ok(automation)
+    }
+
+    /// Load automation from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+/// Get the value of a lane at `time`, linearly interpolating between points.
+pub(crate) fn lane_value(points: &[Point], time: f64) -> Option<f64> {
+    let first = points.first()?;
+    let last = points.last()?;
+
+    if time <= first.time {
+        return Some(first.value);
+    }
+
+    if time >= last.time {
+        return Some(last.value);
+    }
+
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+
+        if time < b.time {
+            let t = (time - a.time) / (b.time - a.time);
+            return Some(a.value + (b.value - a.value) * t);
+        }
+    }
+
+    Some(last.value)
+}
@@ -0,0 +1,104 @@
+use std::{ffi::c_void, mem};
+
+use vst3_sys::{
+    base::{kInvalidArgument, kResultFalse, kResultOk, tresult},
+    utils::SharedVstPtr,
+    vst::{IParamValueQueue, IParameterChanges},
+    VST3,
+};
+
+/// The input parameter changes of a block, the way a host passes automation to the processor.
+#[VST3(implements(IParameterChanges))]
+pub(crate) struct ParamChanges {
+    queues: Vec<Box<ParamQueue>>,
+    len: usize,
+}
+
+impl ParamChanges {
+    pub fn new() -> Box<Self> {
+        Self::allocate(Vec::new(), 0)
+    }
+
+    /// Remove the changes of the previous block.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Add a single point for the parameter `id` at the start of the block.
+    pub fn push(&mut self, id: u32, value: f64) {
+        if self.len == self.queues.len() {
+            self.queues.push(ParamQueue::new());
+        }
+
+        let queue = &mut self.queues[self.len];
+        queue.id = id;
+        queue.value = value;
+
+        self.len += 1;
+    }
+
+    /// Get the changes as the pointer stored in `ProcessData`.
+    pub fn as_shared(&self) -> SharedVstPtr<dyn IParameterChanges> {
+        unsafe { mem::transmute(self as *const Self as *mut c_void) }
+    }
+}
+
+impl IParameterChanges for ParamChanges {
+    unsafe fn get_parameter_count(&self) -> i32 {
+        self.len as i32
+    }
+
+    unsafe fn get_parameter_data(&self, index: i32) -> SharedVstPtr<dyn IParamValueQueue> {
+        match self.queues[..self.len].get(index as usize) {
+            Some(queue) => mem::transmute(&**queue as *const ParamQueue as *mut c_void),
+            None => mem::transmute(std::ptr::null_mut::<c_void>()),
+        }
+    }
+
+    unsafe fn add_parameter_data(
+        &self,
+        _id: *const u32,
+        _index: *mut i32,
+    ) -> SharedVstPtr<dyn IParamValueQueue> {
+        // the changes are input only
+        mem::transmute(std::ptr::null_mut::<c_void>())
+    }
+}
+
+/// A queue holding a single point at the start of the block.
+#[VST3(implements(IParamValueQueue))]
+pub(crate) struct ParamQueue {
+    id: u32,
+    value: f64,
+}
+
+impl ParamQueue {
+    fn new() -> Box<Self> {
+        Self::allocate(0, 0.0)
+    }
+}
+
+impl IParamValueQueue for ParamQueue {
+    unsafe fn get_parameter_id(&self) -> u32 {
+        self.id
+    }
+
+    unsafe fn get_point_count(&self) -> i32 {
+        1
+    }
+
+    unsafe fn get_point(&self, index: i32, sample_offset: *mut i32, value: *mut f64) -> tresult {
+        if index != 0 {
+            return kInvalidArgument;
+        }
+
+        *sample_offset = 0;
+        *value = self.value;
+
+        kResultOk
+    }
+
+    unsafe fn add_point(&self, _sample_offset: i32, _value: f64, _index: *mut i32) -> tresult {
+        kResultFalse
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, io, path::PathBuf};
+
+/// An error that can occur while rendering.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error.
+    Io(io::Error),
+
+    /// An error reading or writing a WAV file.
+    Wav(hound::Error),
+
+    /// An error parsing an automation file.
+    Json(serde_json::Error),
+
+    /// An error loading the plugin library.
+    Library(libloading::Error),
+
+    /// The path is not a valid VST3 bundle.
+    InvalidBundle(PathBuf),
+
+    /// The library did not return a plugin factory.
+    NoFactory,
+
+    /// The factory does not contain an audio module class.
+    NoAudioModule,
+
+    /// The plugin could not be created.
+    CreateInstance,
+
+    /// The plugin does not implement `IAudioProcessor`.
+    NotAProcessor,
+
+    /// The plugin rejected the processing setup.
+    Setup,
+
+    /// The plugin could not be activated.
+    Activate,
+
+    /// The plugin failed to process a block.
+    Process,
+
+    /// An automation lane refers to a parameter that doesn't exist.
+    UnknownParameter(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Wav(err) => write!(f, "{}", err),
+            Error::Json(err) => write!(f, "invalid automation: {}", err),
+            Error::Library(err) => write!(f, "failed to load plugin: {}", err),
+            Error::InvalidBundle(path) => write!(f, "invalid bundle: {}", path.display()),
+            Error::NoFactory => write!(f, "plugin did not return a factory"),
+            Error::NoAudioModule => write!(f, "plugin has no audio module class"),
+            Error::CreateInstance => write!(f, "failed to create plugin instance"),
+            Error::NotAProcessor => write!(f, "plugin is not an audio processor"),
+            Error::Setup => write!(f, "plugin rejected the processing setup"),
+            Error::Activate => write!(f, "failed to activate plugin"),
+            Error::Process => write!(f, "plugin failed to process"),
+            Error::UnknownParameter(name) => write!(f, "unknown parameter `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<hound::Error> for Error {
+    fn from(err: hound::Error) -> Self {
+        Error::Wav(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<libloading::Error> for Error {
+    fn from(err: libloading::Error) -> Self {
+        Error::Library(err)
+    }
+}
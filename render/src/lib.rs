@@ -0,0 +1,19 @@
+#![warn(missing_docs)]
+
+//! # Ori VST Render
+//! Offline rendering of VST3 plugins built with ori-vst, without a DAW.
+//!
+//! A [`Plugin`] can either be loaded from a `.vst3` bundle, or created directly from a
+//! [`VstPlugin`](ori_vst::VstPlugin) type. Audio is driven through
+//! `IAudioProcessor::process` in offline processing mode.
+
+mod audio;
+mod automation;
+mod changes;
+mod error;
+mod plugin;
+
+pub use audio::*;
+pub use automation::*;
+pub use error::*;
+pub use plugin::*;
@@ -0,0 +1,77 @@
+use std::{env, error::Error, process::ExitCode};
+
+use ori_vst_render::{Audio, Automation, Plugin, Settings};
+
+const USAGE: &str = "\
+usage: ori-vst-render <plugin> <input.wav> <output.wav> [options]
+
+options:
+    --sample-rate <hz>         sample rate reported to the plugin (default: input rate)
+    --block-size <samples>     samples per process call (default: 512)
+    --automation <file.json>   parameter automation
+    --tail <seconds>           seconds to render after the input ends (default: 0)";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+
+    let mut positional = Vec::new();
+    let mut sample_rate = None;
+    let mut block_size = None;
+    let mut automation = None;
+    let mut tail = 0.0;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            "--sample-rate" => sample_rate = Some(value(&mut args, &arg)?.parse::<f64>()?),
+            "--block-size" => block_size = Some(value(&mut args, &arg)?.parse::<usize>()?),
+            "--automation" => automation = Some(value(&mut args, &arg)?),
+            "--tail" => tail = value(&mut args, &arg)?.parse::<f64>()?,
+            _ if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg).into()),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [plugin, input, output] = positional.as_slice() else {
+        return Err(USAGE.into());
+    };
+
+    let input = Audio::read_wav(input)?;
+
+    let automation = match automation {
+        Some(path) => Automation::load(path)?,
+        None => Automation::new(),
+    };
+
+    let sample_rate = sample_rate.unwrap_or(input.sample_rate as f64);
+
+    let mut settings = Settings::new(sample_rate).with_tail((tail * sample_rate) as usize);
+
+    if let Some(block_size) = block_size {
+        settings = settings.with_block_size(block_size);
+    }
+
+    let mut plugin = Plugin::load(plugin)?;
+    let rendered = plugin.render(&input, &automation, &settings)?;
+    rendered.write_wav(output)?;
+
+    Ok(())
+}
+
+fn value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{}`", option))
+}
@@ -0,0 +1,387 @@
+use std::{
+    collections::HashMap,
+    ffi::{c_void, CStr},
+    mem,
+    path::{Path, PathBuf},
+    ptr::{self, NonNull},
+};
+
+use ori_vst::{speaker_arrangement, Factory, PluginClass, VstPlugin, AUDIO_MODULE_CLASS};
+use ori_vst_module_info::Module;
+use vst3_com::{ComInterface, VstPtr};
+use vst3_sys::{
+    base::{kResultOk, IPluginBase, IPluginFactory, PClassInfo},
+    vst::{
+        AudioBusBuffers, BusDirections, BusInfo, IAudioProcessor, IComponent, IEditController,
+        MediaTypes, ParameterInfo, ProcessData, ProcessModes, ProcessSetup, SymbolicSampleSizes,
+    },
+};
+
+use crate::{automation::lane_value, changes::ParamChanges, Audio, Automation, Error, Point};
+
+const K_AUDIO: i32 = MediaTypes::kAudio as i32;
+
+const K_INPUT: i32 = BusDirections::kInput as i32;
+const K_OUTPUT: i32 = BusDirections::kOutput as i32;
+
+const K_OFFLINE: i32 = ProcessModes::kOffline as i32;
+const K_SAMPLE_32: i32 = SymbolicSampleSizes::kSample32 as i32;
+
+/// Settings for an offline render.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    /// The sample rate reported to the plugin.
+    pub sample_rate: f64,
+
+    /// The number of samples processed per block.
+    pub block_size: usize,
+
+    /// The number of samples to render after the end of the input, eg. for reverb tails.
+    pub tail: usize,
+}
+
+impl Settings {
+    /// Create new settings.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            block_size: 512,
+            tail: 0,
+        }
+    }
+
+    /// Set the block size.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Set the number of tail samples.
+    pub fn with_tail(mut self, tail: usize) -> Self {
+        self.tail = tail;
+        self
+    }
+}
+
+/// A plugin instance driven by the offline renderer.
+pub struct Plugin {
+    component: VstPtr<dyn IComponent>,
+    processor: VstPtr<dyn IAudioProcessor>,
+    controller: Option<VstPtr<dyn IEditController>>,
+
+    // the module must outlive every interface above, so it is dropped last
+    _module: Option<Module>,
+}
+
+impl Plugin {
+    /// Create a plugin directly from a [`VstPlugin`] type.
+    pub fn from_plugin<P: VstPlugin>() -> Result<Self, Error> {
+        let factory = Factory::new(vec![PluginClass::new::<P>()]);
+        let factory = Box::into_raw(factory) as *mut c_void;
+
+        unsafe { Self::from_factory(factory, None) }
+    }
+
+    /// Load a plugin from a `.vst3` bundle, or directly from the plugin library.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let binary = bundle_binary(path.as_ref())?;
+
+        unsafe {
+            let module = Module::load(&binary)?;
+            let factory = module.factory()?;
+
+            Self::from_factory(factory, Some(module))
+        }
+    }
+
+    unsafe fn from_factory(factory: *mut c_void, module: Option<Module>) -> Result<Self, Error> {
+        let factory = NonNull::new(factory).ok_or(Error::NoFactory)?;
+        let factory = VstPtr::<dyn IPluginFactory>::owned(factory.cast());
+
+        for index in 0..factory.count_classes() {
+            let mut info = mem::zeroed::<PClassInfo>();
+
+            if factory.get_class_info(index, &mut info) != kResultOk {
+                continue;
+            }
+
+            if CStr::from_ptr(info.category.as_ptr()).to_bytes() != AUDIO_MODULE_CLASS.as_bytes() {
+                continue;
+            }
+
+            let mut obj = ptr::null_mut();
+            let iid = <dyn IComponent as ComInterface>::IID;
+
+            if factory.create_instance(&info.cid, &iid, &mut obj) != kResultOk {
+                return Err(Error::CreateInstance);
+            }
+
+            let obj = NonNull::new(obj).ok_or(Error::CreateInstance)?;
+            let component = VstPtr::<dyn IComponent>::owned(obj.cast());
+
+            let processor = component
+                .cast::<dyn IAudioProcessor>()
+                .ok_or(Error::NotAProcessor)?;
+            let controller = component.cast::<dyn IEditController>();
+
+            component.initialize(ptr::null_mut());
+
+            return Ok(Self {
+                component,
+                processor,
+                controller,
+                _module: module,
+            });
+        }
+
+        Err(Error::NoAudioModule)
+    }
+
+    /// Render `input` through the plugin, applying `automation`.
+    ///
+    /// Automation is passed to the processor as input parameter changes at the start of every
+    /// block, so its resolution is the block size.
+    /// The plugin's reported latency is compensated for, so the output lines up with the input.
+    pub fn render(
+        &mut self,
+        input: &Audio,
+        automation: &Automation,
+        settings: &Settings,
+    ) -> Result<Audio, Error> {
+        unsafe { self.render_inner(input, automation, settings) }
+    }
+
+    unsafe fn render_inner(
+        &mut self,
+        input: &Audio,
+        automation: &Automation,
+        settings: &Settings,
+    ) -> Result<Audio, Error> {
+        let lanes = self.resolve_automation(automation)?;
+
+        let mut input_arrangement = speaker_arrangement(input.channels() as u32);
+        let mut output_arrangement = input_arrangement;
+
+        // plugins may reject the arrangement, in which case they keep their default layout
+        self.processor
+            .set_bus_arrangements(&mut input_arrangement, 1, &mut output_arrangement, 1);
+
+        let input_channels = self.main_bus_channels(K_INPUT);
+        let output_channels = self.main_bus_channels(K_OUTPUT);
+
+        let block_size = settings.block_size.max(1);
+
+        let setup = ProcessSetup {
+            process_mode: K_OFFLINE,
+            symbolic_sample_size: K_SAMPLE_32,
+            max_samples_per_block: block_size as i32,
+            sample_rate: settings.sample_rate,
+        };
+
+        if self.processor.setup_processing(&setup) != kResultOk {
+            return Err(Error::Setup);
+        }
+
+        if self.component.set_active(1) != kResultOk {
+            return Err(Error::Activate);
+        }
+
+        self.processor.set_processing(1);
+
+        let latency = self.processor.get_latency_samples() as usize;
+        let length = input.samples() + latency + settings.tail;
+
+        let mut output = Audio::new(output_channels, length, settings.sample_rate as u32);
+
+        let mut input_block = vec![vec![0.0f32; block_size]; input_channels];
+        let mut output_block = vec![vec![0.0f32; block_size]; output_channels];
+
+        let mut changes = ParamChanges::new();
+
+        let mut result = Ok(());
+        let mut position = 0;
+
+        while position < length {
+            let samples = usize::min(block_size, length - position);
+
+            let time = position as f64 / settings.sample_rate;
+            changes.clear();
+
+            for (id, points) in &lanes {
+                if let Some(value) = lane_value(points, time) {
+                    changes.push(*id, value.clamp(0.0, 1.0));
+                }
+            }
+
+            for (channel, block) in input_block.iter_mut().enumerate() {
+                block.fill(0.0);
+
+                if let Some(source) = input.channels.get(channel) {
+                    let end = usize::min(position + samples, source.len());
+
+                    if position < end {
+                        block[..end - position].copy_from_slice(&source[position..end]);
+                    }
+                }
+            }
+
+            for block in output_block.iter_mut() {
+                block.fill(0.0);
+            }
+
+            let mut input_ptrs: Vec<*mut f32> =
+                input_block.iter_mut().map(|c| c.as_mut_ptr()).collect();
+            let mut output_ptrs: Vec<*mut f32> =
+                output_block.iter_mut().map(|c| c.as_mut_ptr()).collect();
+
+            let mut input_bus = mem::zeroed::<AudioBusBuffers>();
+            input_bus.num_channels = input_channels as i32;
+            input_bus.buffers = input_ptrs.as_mut_ptr().cast();
+
+            let mut output_bus = mem::zeroed::<AudioBusBuffers>();
+            output_bus.num_channels = output_channels as i32;
+            output_bus.buffers = output_ptrs.as_mut_ptr().cast();
+
+            let mut data = mem::zeroed::<ProcessData>();
+            data.process_mode = K_OFFLINE;
+            data.symbolic_sample_size = K_SAMPLE_32;
+            data.num_samples = samples as i32;
+            data.num_inputs = (input_channels > 0) as i32;
+            data.num_outputs = (output_channels > 0) as i32;
+            data.inputs = &mut input_bus;
+            data.outputs = &mut output_bus;
+            data.input_param_changes = changes.as_shared();
+
+            if self.processor.process(&mut data) != kResultOk {
+                result = Err(Error::Process);
+                break;
+            }
+
+            for (channel, block) in output_block.iter().enumerate() {
+                output.channels[channel][position..position + samples]
+                    .copy_from_slice(&block[..samples]);
+            }
+
+            position += samples;
+        }
+
+        self.processor.set_processing(0);
+        self.component.set_active(0);
+
+        result?;
+
+        for channel in output.channels.iter_mut() {
+            channel.drain(..latency);
+        }
+
+        Ok(output)
+    }
+
+    unsafe fn main_bus_channels(&self, dir: i32) -> usize {
+        if self.component.get_bus_count(K_AUDIO, dir) == 0 {
+            return 0;
+        }
+
+        let mut info = mem::zeroed::<BusInfo>();
+
+        if self.component.get_bus_info(K_AUDIO, dir, 0, &mut info) != kResultOk {
+            return 0;
+        }
+
+        info.channel_count as usize
+    }
+
+    unsafe fn resolve_automation<'a>(
+        &self,
+        automation: &'a Automation,
+    ) -> Result<Vec<(u32, &'a [Point])>, Error> {
+        if automation.lanes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let Some(ref controller) = self.controller else {
+            let name = automation.lanes.keys().next().unwrap();
+            return Err(Error::UnknownParameter(name.clone()));
+        };
+
+        let mut ids = HashMap::new();
+
+        for index in 0..controller.get_parameter_count() {
+            let mut info = mem::zeroed::<ParameterInfo>();
+
+            if controller.get_parameter_info(index, &mut info) != kResultOk {
+                continue;
+            }
+
+            let len = info.title.iter().position(|&c| c == 0);
+            let title = &info.title[..len.unwrap_or(info.title.len())];
+            let title = title.iter().map(|&c| c as u16).collect::<Vec<_>>();
+            let title = String::from_utf16_lossy(&title);
+
+            ids.insert(info.id.to_string(), info.id);
+            ids.insert(title, info.id);
+        }
+
+        let mut lanes = Vec::with_capacity(automation.lanes.len());
+
+        for (name, points) in &automation.lanes {
+            match ids.get(name) {
+                Some(&id) => lanes.push((id, points.as_slice())),
+                None => return Err(Error::UnknownParameter(name.clone())),
+            }
+        }
+
+        Ok(lanes)
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        unsafe {
+            self.component.terminate();
+        }
+    }
+}
+
+/// Find the plugin library inside a `.vst3` bundle.
+///
+/// If `path` is a file, it is assumed to be the library itself.
+pub fn bundle_binary(path: &Path) -> Result<PathBuf, Error> {
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    let name = path
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| Error::InvalidBundle(path.to_path_buf()))?;
+
+    let contents = path.join("Contents");
+
+    #[cfg(target_os = "linux")]
+    let binary = contents
+        .join(format!("{}-linux", arch()))
+        .join(format!("{}.so", name));
+
+    #[cfg(target_os = "windows")]
+    let binary = contents
+        .join(format!("{}-win", arch()))
+        .join(format!("{}.vst3", name));
+
+    #[cfg(target_os = "macos")]
+    let binary = contents.join("MacOS").join(name);
+
+    match binary.is_file() {
+        true => Ok(binary),
+        false => Err(Error::InvalidBundle(path.to_path_buf())),
+    }
+}
+
+fn arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "i386",
+        #[cfg(target_os = "windows")]
+        "aarch64" => "arm64",
+        arch => arch,
+    }
+}
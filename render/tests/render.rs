@@ -0,0 +1,55 @@
+#[path = "../../examples/gain.rs"]
+mod gain;
+
+use ori_vst_render::{Audio, Automation, Plugin, Settings};
+
+fn input() -> Audio {
+    let mut audio = Audio::new(2, 1000, 48000);
+
+    for channel in audio.channels.iter_mut() {
+        channel.fill(0.5);
+    }
+
+    audio
+}
+
+#[test]
+fn render_default_gain() {
+    let mut plugin = Plugin::from_plugin::<gain::GainPlugin>().unwrap();
+    let settings = Settings::new(48000.0).with_block_size(64);
+
+    let output = plugin
+        .render(&input(), &Automation::new(), &settings)
+        .unwrap();
+
+    assert_eq!(output.channels(), 2);
+    assert_eq!(output.samples(), 1000);
+    assert!(output
+        .channels
+        .iter()
+        .flatten()
+        .all(|&sample| sample == 0.5));
+}
+
+#[test]
+fn render_automated_gain() {
+    let mut plugin = Plugin::from_plugin::<gain::GainPlugin>().unwrap();
+    let settings = Settings::new(48000.0).with_block_size(100);
+
+    // the gain ranges from 0 to 20, ramping from 0 at the start to 10 after 500 samples
+    let automation = Automation::from_json(
+        r#"{ "Gain": [{ "time": 0.0, "value": 0.0 }, { "time": 0.0104166, "value": 0.5 }] }"#,
+    )
+    .unwrap();
+
+    let output = plugin.render(&input(), &automation, &settings).unwrap();
+
+    for channel in &output.channels {
+        assert_eq!(channel[0], 0.0);
+        assert!((channel[999] - 5.0).abs() < 1e-4);
+
+        // automation is applied per block
+        assert_eq!(channel[100], channel[199]);
+        assert!(channel[100] < channel[200]);
+    }
+}
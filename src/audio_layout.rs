@@ -1,3 +1,5 @@
+use vst3_sys::vst::{k50, k51, k70Cine, k71Cine, kEmpty, kMono, kStereo, SpeakerArrangement};
+
 use crate::Buffer;
 
 /// The layout of the audio ports.
@@ -188,6 +190,23 @@ impl AudioPort {
     }
 }
 
+/// Get the VST3 speaker arrangement for `channels` channels.
+///
+/// Channel counts without a standard arrangement use one speaker per channel, up to 64.
+pub fn speaker_arrangement(channels: u32) -> SpeakerArrangement {
+    match channels {
+        0 => kEmpty,
+        1 => kMono,
+        2 => kStereo,
+        5 => k50,
+        6 => k51,
+        7 => k70Cine,
+        8 => k71Cine,
+        n if n >= 64 => u64::MAX,
+        n => (1 << n) - 1,
+    }
+}
+
 /// A collection of audio buffers.
 #[derive(Debug, Default)]
 pub(crate) struct Buffers {
@@ -61,6 +61,16 @@ impl<'a> Buffer<'a> {
         self.channels.len()
     }
 
+    /// Get the channel at the given `index`.
+    pub fn channel(&self, index: usize) -> &[f32] {
+        self.channels[index]
+    }
+
+    /// Get the channel at the given `index` mutably.
+    pub fn channel_mut(&mut self, index: usize) -> &mut [f32] {
+        self.channels[index]
+    }
+
     /// Set the channel at the given `index`.
     ///
     /// The channel should contain `self.samples()` samples.
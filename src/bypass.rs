@@ -0,0 +1,211 @@
+use std::cell::Cell;
+
+use crate::{Bool, Buffer, BufferLayout, Param, ParamFlags, ParamInfo, Params, Unit};
+
+/// The length of the crossfade when the bypass is toggled.
+const FADE_SECONDS: f32 = 0.005;
+
+/// The bypass parameter provided by the framework.
+///
+/// While bypassed the plugin isn't processed, instead the input is passed to the output through
+/// a delay line matching the reported latency.
+pub(crate) struct Bypass {
+    param: Bool,
+
+    /// Whether the plugin declares its own bypass parameter, found on the first lookup.
+    plugin_bypass: Cell<Option<bool>>,
+    delay: Vec<Vec<f32>>,
+    dry: Vec<Vec<f32>>,
+    position: usize,
+    fade: f32,
+    step: f32,
+}
+
+impl Default for Bypass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bypass {
+    /// The identifier of the bypass parameter in the plugin state.
+    pub const IDENTIFIER: &'static str = "__bypass";
+
+    pub fn new() -> Self {
+        Self {
+            param: Bool::new(false).name("Bypass"),
+            plugin_bypass: Cell::new(None),
+            delay: Vec::new(),
+            dry: Vec::new(),
+            position: 0,
+            fade: 0.0,
+            step: 1.0,
+        }
+    }
+
+    pub fn is_bypassed(&self) -> bool {
+        *self.param
+    }
+
+    /// Whether the plugin needs to be processed, this is false once fully faded to bypass.
+    pub fn is_processing(&self) -> bool {
+        !self.is_bypassed() || self.fade < 1.0
+    }
+
+    /// Append the bypass parameter to `params`.
+    pub fn with_params<'a>(&'a mut self, params: &'a mut dyn Params) -> BypassParams<'a> {
+        BypassParams {
+            params,
+            bypass: &mut self.param,
+            plugin_bypass: &self.plugin_bypass,
+        }
+    }
+
+    /// Allocate the delay line, this must be called when the plugin is activated.
+    pub fn activate(&mut self, channels: usize, latency: u32, layout: &BufferLayout) {
+        self.delay = vec![vec![0.0; latency as usize]; channels];
+        self.dry = vec![vec![0.0; layout.max_buffer_size]; channels];
+        self.position = 0;
+        self.fade = if self.is_bypassed() { 1.0 } else { 0.0 };
+        self.step = 1.0 / (FADE_SECONDS * layout.sample_rate).max(1.0);
+    }
+
+    /// Capture the dry signal of `buffer` before the plugin processes it.
+    ///
+    /// Only the first `inputs` channels of `buffer` contain input, the rest are silent.
+    pub fn capture(&mut self, buffer: &Buffer<'_>, inputs: usize) {
+        let samples = usize::min(buffer.samples(), self.max_samples());
+
+        for (channel, (dry, delay)) in self.dry.iter_mut().zip(&mut self.delay).enumerate() {
+            let mut position = self.position;
+
+            for (sample, dry) in dry[..samples].iter_mut().enumerate() {
+                let input = match channel < inputs && channel < buffer.channels() {
+                    true => buffer.channel(channel)[sample],
+                    false => 0.0,
+                };
+
+                if delay.is_empty() {
+                    *dry = input;
+                    continue;
+                }
+
+                *dry = delay[position];
+                delay[position] = input;
+                position = (position + 1) % delay.len();
+            }
+        }
+
+        if let Some(delay) = self.delay.first().filter(|delay| !delay.is_empty()) {
+            self.position = (self.position + samples) % delay.len();
+        }
+    }
+
+    /// Mix the captured dry signal into `buffer`, crossfading when the bypass is toggled.
+    pub fn mix(&mut self, buffer: &mut Buffer<'_>) {
+        let target = if self.is_bypassed() { 1.0 } else { 0.0 };
+
+        if self.fade == 0.0 && target == 0.0 {
+            return;
+        }
+
+        let samples = usize::min(buffer.samples(), self.max_samples());
+        let mut end = self.fade;
+
+        for channel in 0..buffer.channels() {
+            let output = buffer.channel_mut(channel);
+            let mut fade = self.fade;
+
+            for (sample, output) in output[..samples].iter_mut().enumerate() {
+                fade = approach(fade, target, self.step);
+
+                let dry = self.dry.get(channel).map_or(0.0, |dry| dry[sample]);
+                *output = *output * (1.0 - fade) + dry * fade;
+            }
+
+            end = fade;
+        }
+
+        self.fade = end;
+    }
+
+    fn max_samples(&self) -> usize {
+        self.dry.first().map_or(0, Vec::len)
+    }
+}
+
+fn approach(value: f32, target: f32, step: f32) -> f32 {
+    match value < target {
+        true => f32::min(value + step, target),
+        false => f32::max(value - step, target),
+    }
+}
+
+/// The parameters of a plugin, followed by the bypass parameter.
+pub(crate) struct BypassParams<'a> {
+    params: &'a mut dyn Params,
+    bypass: &'a mut Bool,
+    plugin_bypass: &'a Cell<Option<bool>>,
+}
+
+impl BypassParams<'_> {
+    /// Whether the plugin declares its own bypass parameter.
+    ///
+    /// Finding it allocates the infos of every parameter, so it's only done once, the flags of
+    /// the parameters don't change.
+    fn plugin_bypass(&self) -> bool {
+        if let Some(plugin_bypass) = self.plugin_bypass.get() {
+            return plugin_bypass;
+        }
+
+        let plugin_bypass = (0..self.params.count()).any(|index| {
+            let info = self.params.info(index);
+            info.is_some_and(|info| info.flags.contains(ParamFlags::BYPASS))
+        });
+
+        self.plugin_bypass.set(Some(plugin_bypass));
+        plugin_bypass
+    }
+}
+
+impl Params for BypassParams<'_> {
+    fn count(&self) -> usize {
+        self.params.count() + 1
+    }
+
+    fn info(&self, index: usize) -> Option<ParamInfo> {
+        if index != self.params.count() {
+            return self.params.info(index);
+        }
+
+        let mut flags = ParamFlags::AUTOMATE;
+
+        // a plugin declaring its own bypass parameter keeps the flag to itself
+        if !self.plugin_bypass() {
+            flags |= ParamFlags::BYPASS;
+        }
+
+        Some(ParamInfo {
+            name: String::from("Bypass"),
+            short: String::from("Bypass"),
+            unit: Unit::Binary,
+            step_count: 1,
+            default_normalized: self.bypass.default_normalized(),
+            flags,
+        })
+    }
+
+    fn param(&mut self, index: usize) -> Option<&mut dyn Param> {
+        match index == self.params.count() {
+            true => Some(self.bypass),
+            false => self.params.param(index),
+        }
+    }
+
+    fn identifier(&self, index: usize) -> Option<String> {
+        match index == self.params.count() {
+            true => Some(String::from(Bypass::IDENTIFIER)),
+            false => self.params.identifier(index),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::Redraw;
+
+/// Create a single-producer single-consumer ring buffer that holds `capacity` values.
+///
+/// This is useful for streaming samples, eg. for a waveform display.
+pub fn ring_buffer<T: Copy + Send>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let buffer = (0..capacity + 1)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+
+    let ring = Arc::new(Ring {
+        buffer,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+
+    let producer = Producer {
+        ring: ring.clone(),
+        redraw: None,
+    };
+
+    (producer, Consumer { ring })
+}
+
+struct Ring<T> {
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn next(&self, index: usize) -> usize {
+        (index + 1) % self.buffer.len()
+    }
+}
+
+/// The producing end of a [`ring_buffer`], owned by the audio thread.
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+    redraw: Option<Redraw>,
+}
+
+impl<T: Copy> Producer<T> {
+    /// Request a redraw of the editor whenever values are pushed.
+    ///
+    /// Only the first request before the editor redraws wakes it, see [`Redraw`].
+    pub fn set_redraw(&mut self, redraw: Redraw) {
+        self.redraw = Some(redraw);
+    }
+
+    /// Push a value, returning it if the buffer is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        self.push_value(value)?;
+
+        if let Some(ref redraw) = self.redraw {
+            redraw.request();
+        }
+
+        Ok(())
+    }
+
+    /// Push as many `values` as fit, returning the number pushed.
+    pub fn push_slice(&mut self, values: &[T]) -> usize {
+        let mut pushed = 0;
+
+        for &value in values {
+            if self.push_value(value).is_err() {
+                break;
+            }
+
+            pushed += 1;
+        }
+
+        if let Some(ref redraw) = self.redraw {
+            if pushed > 0 {
+                redraw.request();
+            }
+        }
+
+        pushed
+    }
+
+    fn push_value(&mut self, value: T) -> Result<(), T> {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        let next = self.ring.next(tail);
+
+        if next == self.ring.head.load(Ordering::Acquire) {
+            return Err(value);
+        }
+
+        // SAFETY: only the producer writes to the slot at `tail`, and the consumer won't read it
+        // until `tail` is advanced.
+        unsafe { (*self.ring.buffer[tail].get()).write(value) };
+        self.ring.tail.store(next, Ordering::Release);
+
+        Ok(())
+    }
+}
+
+/// The consuming end of a [`ring_buffer`], owned by the user interface.
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+impl<T: Copy> Consumer<T> {
+    /// Pop the oldest value.
+    pub fn pop(&mut self) -> Option<T> {
+        let head = self.ring.head.load(Ordering::Relaxed);
+
+        if head == self.ring.tail.load(Ordering::Acquire) {
+            return None;
+        }
+
+        // SAFETY: the producer initialized the slot at `head` before advancing `tail`.
+        let value = unsafe { (*self.ring.buffer[head].get()).assume_init() };
+        self.ring
+            .head
+            .store(self.ring.next(head), Ordering::Release);
+
+        Some(value)
+    }
+
+    /// Pop every value currently in the buffer.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.pop())
+    }
+
+    /// Get the number of values in the buffer.
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Acquire);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+
+        (tail + self.ring.buffer.len() - head) % self.ring.buffer.len()
+    }
+
+    /// Check if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+const INDEX: u8 = 0b011;
+const DIRTY: u8 = 0b100;
+
+/// Create a triple buffer, starting out with `value`.
+///
+/// The reader always sees the latest complete value, which is useful for eg. spectra.
+pub fn triple_buffer<T: Clone + Send>(value: T) -> (Input<T>, Output<T>) {
+    let triple = Arc::new(Triple {
+        slots: [
+            UnsafeCell::new(value.clone()),
+            UnsafeCell::new(value.clone()),
+            UnsafeCell::new(value),
+        ],
+        back: AtomicU8::new(2),
+    });
+
+    let input = Input {
+        triple: triple.clone(),
+        index: 0,
+        redraw: None,
+    };
+
+    let output = Output { triple, index: 1 };
+
+    (input, output)
+}
+
+struct Triple<T> {
+    slots: [UnsafeCell<T>; 3],
+    back: AtomicU8,
+}
+
+unsafe impl<T: Send> Sync for Triple<T> {}
+
+/// The writing end of a [`triple_buffer`], owned by the audio thread.
+pub struct Input<T> {
+    triple: Arc<Triple<T>>,
+    index: u8,
+    redraw: Option<Redraw>,
+}
+
+impl<T> Input<T> {
+    /// Request a redraw of the editor whenever a value is published.
+    ///
+    /// Only the first request before the editor redraws wakes it, see [`Redraw`].
+    pub fn set_redraw(&mut self, redraw: Redraw) {
+        self.redraw = Some(redraw);
+    }
+
+    /// Get the value being written, this is not visible to the reader until [`Input::publish`].
+    pub fn input_mut(&mut self) -> &mut T {
+        // SAFETY: the slot at `index` is owned by the writer.
+        unsafe { &mut *self.triple.slots[self.index as usize].get() }
+    }
+
+    /// Publish the value being written.
+    pub fn publish(&mut self) {
+        let back = self.triple.back.swap(self.index | DIRTY, Ordering::AcqRel);
+        self.index = back & INDEX;
+
+        if let Some(ref redraw) = self.redraw {
+            redraw.request();
+        }
+    }
+
+    /// Write and publish `value`.
+    pub fn write(&mut self, value: T) {
+        *self.input_mut() = value;
+        self.publish();
+    }
+}
+
+/// The reading end of a [`triple_buffer`], owned by the user interface.
+pub struct Output<T> {
+    triple: Arc<Triple<T>>,
+    index: u8,
+}
+
+impl<T> Output<T> {
+    /// Check if a new value has been published since the last read.
+    pub fn updated(&self) -> bool {
+        self.triple.back.load(Ordering::Acquire) & DIRTY != 0
+    }
+
+    /// Read the latest published value.
+    pub fn read(&mut self) -> &T {
+        if self.updated() {
+            let back = self.triple.back.swap(self.index, Ordering::AcqRel);
+            self.index = back & INDEX;
+        }
+
+        // SAFETY: the slot at `index` is owned by the reader.
+        unsafe { &*self.triple.slots[self.index as usize].get() }
+    }
+}
@@ -139,17 +139,10 @@ impl<P: VstPlugin> IComponent for RawPlugin<P> {
             return kResultOk;
         }
 
-        if let Some(buffer_layout) = self.state.buffer_layout() {
-            let audio_layout = self.state.audio_layout();
-            self.state.allocate_buffers(&audio_layout);
-
-            let config = plugin.activate(&audio_layout, &buffer_layout);
-            self.state.set_latency(config.latency);
-
-            return kResultOk;
+        match self.state.activate(&mut plugin) {
+            true => kResultOk,
+            false => kResultFalse,
         }
-
-        kResultFalse
     }
 
     unsafe fn set_state(&self, state: SharedVstPtr<dyn IBStream>) -> tresult {
@@ -157,9 +150,6 @@ impl<P: VstPlugin> IComponent for RawPlugin<P> {
             return kInvalidArgument;
         };
 
-        let mut plugin = self.state.plugin.lock();
-        let params = plugin.params();
-
         let mut current = 0;
         let mut end = 0;
 
@@ -189,14 +179,17 @@ impl<P: VstPlugin> IComponent for RawPlugin<P> {
             return kInvalidArgument;
         };
 
-        for i in 0..params.count() {
-            let id = params.identifier(i).unwrap();
-            let param = params.param(i).unwrap();
+        self.state.with_params(|params| {
+            for i in 0..params.count() {
+                let id = params.identifier(i).unwrap();
+                let param = params.param(i).unwrap();
 
-            if let Some(value) = values.get(&id) {
-                param.set(*value);
+                if let Some(value) = values.get(&id) {
+                    param.set(*value);
+                    self.state.set_by_host(i);
+                }
             }
-        }
+        });
 
         kResultOk
     }
@@ -206,17 +199,16 @@ impl<P: VstPlugin> IComponent for RawPlugin<P> {
             return kInvalidArgument;
         };
 
-        let mut plugin = self.state.plugin.lock();
-        let params = plugin.params();
-
         let mut values: HashMap<String, f32> = HashMap::new();
 
-        for i in 0..params.count() {
-            let id = params.identifier(i).unwrap();
-            let param = params.param(i).unwrap();
+        self.state.with_params(|params| {
+            for i in 0..params.count() {
+                let id = params.identifier(i).unwrap();
+                let param = params.param(i).unwrap();
 
-            values.insert(id, param.get());
-        }
+                values.insert(id, param.get());
+            }
+        });
 
         if let Ok(bytes) = serde_bencode::to_bytes(&values) {
             let mut bytes_written = 0;
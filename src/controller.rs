@@ -4,10 +4,10 @@ use vst3_com::VstPtr;
 use vst3_sys::{
     base::{kResultOk, tresult, FIDString, IBStream},
     utils::SharedVstPtr,
-    vst::{IComponentHandler, IEditController, ParameterInfo, TChar},
+    vst::{IComponentHandler, IEditController, ParameterFlags, ParameterInfo, TChar},
 };
 
-use crate::{util, RawPlugin, RawView, VstPlugin};
+use crate::{util, ParamFlags, RawPlugin, RawView, VstPlugin};
 
 pub struct ComponentHandler {
     vst: VstPtr<dyn IComponentHandler>,
@@ -23,6 +23,29 @@ impl Deref for ComponentHandler {
     }
 }
 
+/// Convert [`ParamFlags`] to VST3 `ParameterFlags`, flags without a VST3 equivalent are dropped.
+fn parameter_flags(flags: ParamFlags) -> i32 {
+    const FLAGS: [(ParamFlags, ParameterFlags); 7] = [
+        (ParamFlags::AUTOMATE, ParameterFlags::kCanAutomate),
+        (ParamFlags::READ_ONLY, ParameterFlags::kIsReadOnly),
+        (ParamFlags::WRAP, ParameterFlags::kIsWrapAround),
+        (ParamFlags::LIST, ParameterFlags::kIsList),
+        (ParamFlags::HIDDEN, ParameterFlags::kIsHidden),
+        (ParamFlags::PROGRAM_CHANGE, ParameterFlags::kIsProgramChange),
+        (ParamFlags::BYPASS, ParameterFlags::kIsBypass),
+    ];
+
+    let mut vst_flags = 0;
+
+    for (flag, vst_flag) in FLAGS {
+        if flags.contains(flag) {
+            vst_flags |= vst_flag as i32;
+        }
+    }
+
+    vst_flags
+}
+
 impl<P: VstPlugin> IEditController for RawPlugin<P> {
     unsafe fn set_component_state(&self, _state: SharedVstPtr<dyn IBStream>) -> tresult {
         kResultOk
@@ -37,14 +60,13 @@ impl<P: VstPlugin> IEditController for RawPlugin<P> {
     }
 
     unsafe fn get_parameter_count(&self) -> i32 {
-        let mut plugin = self.state.plugin.lock();
-        plugin.params().count() as i32
+        self.state.with_params(|params| params.count() as i32)
     }
 
     unsafe fn get_parameter_info(&self, index: i32, out_info: *mut ParameterInfo) -> tresult {
-        let mut plugin = self.state.plugin.lock();
+        let info = self.state.with_params(|params| params.info(index as usize));
 
-        if let Some(info) = plugin.params().info(index as usize) {
+        if let Some(info) = info {
             let out_info = &mut *out_info;
 
             out_info.id = index as u32;
@@ -54,7 +76,7 @@ impl<P: VstPlugin> IEditController for RawPlugin<P> {
             out_info.unit_id = info.unit.id();
             out_info.step_count = info.step_count;
             out_info.default_normalized_value = info.default_normalized as f64;
-            out_info.flags = info.flags.bits() as i32;
+            out_info.flags = parameter_flags(info.flags);
         }
 
         kResultOk
@@ -66,11 +88,12 @@ impl<P: VstPlugin> IEditController for RawPlugin<P> {
         normalized: f64,
         string: *mut TChar,
     ) -> tresult {
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
-        let plain = param.plain(normalized as f32);
+        let s = self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+            let plain = param.plain(normalized as f32);
 
-        let s = param.to_string(plain);
+            param.to_string(plain)
+        });
 
         let string = slice::from_raw_parts_mut(string, 128);
         string.fill(0);
@@ -90,39 +113,46 @@ impl<P: VstPlugin> IEditController for RawPlugin<P> {
         let string = slice::from_raw_parts(string.cast(), len);
         let s = String::from_utf16_lossy(string);
 
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
-        let plain = param.from_string(&s);
-        *normalized = param.normalize(plain) as f64;
+        self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+            let plain = param.from_string(&s);
+            *normalized = param.normalize(plain) as f64;
+        });
 
         kResultOk
     }
 
     unsafe fn normalized_param_to_plain(&self, id: u32, normalized: f64) -> f64 {
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
-        param.plain(normalized as f32) as f64
+        self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+            param.plain(normalized as f32) as f64
+        })
     }
 
     unsafe fn plain_param_to_normalized(&self, id: u32, plain: f64) -> f64 {
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
-        param.normalize(plain as f32) as f64
+        self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+            param.normalize(plain as f32) as f64
+        })
     }
 
     unsafe fn get_param_normalized(&self, id: u32) -> f64 {
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
-        let plain = param.get();
-        param.normalize(plain) as f64
+        self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+            let plain = param.get();
+            param.normalize(plain) as f64
+        })
     }
 
     unsafe fn set_param_normalized(&self, id: u32, value: f64) -> tresult {
-        let mut plugin = self.state.plugin.lock();
-        let param = plugin.params().param(id as usize).unwrap();
+        self.state.with_params(|params| {
+            let param = params.param(id as usize).unwrap();
+
+            let plain = param.plain(value as f32);
+            param.set(plain);
 
-        let plain = param.plain(value as f32);
-        param.set(plain);
+            self.state.set_by_host(id as usize);
+        });
 
         if let Some(editor) = self.state.editor.lock().as_ref() {
             editor.rebuild();
@@ -151,3 +181,26 @@ impl<P: VstPlugin> IEditController for RawPlugin<P> {
 }
 
 impl<P: VstPlugin> RawPlugin<P> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_flags_match_vst3() {
+        assert_eq!(parameter_flags(ParamFlags::empty()), 0);
+        assert_eq!(parameter_flags(ParamFlags::AUTOMATE), 1);
+        assert_eq!(parameter_flags(ParamFlags::READ_ONLY), 1 << 1);
+        assert_eq!(parameter_flags(ParamFlags::WRAP), 1 << 2);
+        assert_eq!(parameter_flags(ParamFlags::LIST), 1 << 3);
+        assert_eq!(parameter_flags(ParamFlags::HIDDEN), 1 << 4);
+        assert_eq!(parameter_flags(ParamFlags::PROGRAM_CHANGE), 1 << 15);
+        assert_eq!(parameter_flags(ParamFlags::BYPASS), 1 << 16);
+    }
+
+    #[test]
+    fn parameter_flags_combine() {
+        let flags = ParamFlags::AUTOMATE | ParamFlags::BYPASS | ParamFlags::DISCRETE;
+        assert_eq!(parameter_flags(flags), 1 | (1 << 16));
+    }
+}
@@ -1,3 +1,14 @@
+use std::path::PathBuf;
+
+use ori::{
+    core::text::{FontSource, Fonts},
+    prelude::{AnyView, Point},
+};
+use ori_skia::SkiaFonts;
+
+/// Builds the root view of the editor.
+pub(crate) type EditorUi<P> = fn(&mut P) -> Box<dyn AnyView<P>>;
+
 pub trait EditorHandle: Send + Sync {
     fn quit(&self);
 
@@ -8,4 +19,334 @@ pub trait EditorHandle: Send + Sync {
     fn resizable(&self) -> bool;
 
     fn rebuild(&self);
+
+    fn scale(&self) -> f32;
+
+    fn set_scale(&self, scale: f32);
+
+    /// Constrain a physical size to the size constraints of the editor.
+    fn constrain(&self, width: u32, height: u32) -> (u32, u32);
+
+    /// Handle a key forwarded by the host, returning true if the editor consumed it.
+    ///
+    /// The arguments are the raw values of `IPlugView::on_key_down` and `on_key_up`. This is
+    /// called on the host's UI thread and must not wait for the editor, so keys that can't be
+    /// handled before returning are reported as not consumed.
+    fn key(&self, key: u16, key_code: i16, modifiers: i16, pressed: bool) -> bool;
+
+    /// Handle a mouse wheel forwarded by the host, returning true if the editor handled it.
+    fn wheel(&self, distance: f32) -> bool;
+}
+
+/// The frame rate of an animating editor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameRate {
+    /// A fixed number of frames per second, between 1 and 480.
+    Fps(f32),
+
+    /// The refresh rate of the display.
+    Display,
+}
+
+/// Files dragged over the editor, sent to the views as a command.
+///
+/// Positions are in logical pixels, relative to the window.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileDrop {
+    /// Files are dragged over the editor.
+    Hovered {
+        /// The position of the pointer.
+        position: Point,
+
+        /// The paths of the dragged files.
+        paths: Vec<PathBuf>,
+    },
+
+    /// The drag left the editor without dropping.
+    Left,
+
+    /// Files were dropped on the editor.
+    Dropped {
+        /// The position of the drop.
+        position: Point,
+
+        /// The paths of the dropped files.
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Options for the editor of a plugin.
+#[derive(Clone, Debug)]
+pub struct EditorOptions {
+    /// The frame rate while animating.
+    pub frame_rate: FrameRate,
+
+    /// Whether the editor starts out animating.
+    pub animate: bool,
+
+    /// The minimum size of the editor.
+    pub min_size: Option<(u32, u32)>,
+
+    /// The maximum size of the editor.
+    pub max_size: Option<(u32, u32)>,
+
+    /// The fixed aspect ratio of the editor, `width / height`.
+    pub aspect_ratio: Option<f32>,
+
+    /// The steps the size of the editor snaps to.
+    pub size_step: Option<(u32, u32)>,
+
+    /// Whether the editor is rendered on the CPU, even when OpenGL is available.
+    pub software: bool,
+
+    /// Whether the editor runs on the host's run loop on Linux, instead of its own thread.
+    pub run_loop: bool,
+
+    /// Fonts embedded in the plugin, loaded when the editor opens.
+    pub fonts: Vec<&'static [u8]>,
+
+    /// The default font family, `Roboto` if `None`.
+    pub default_font: Option<String>,
+}
+
+impl Default for EditorOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EditorOptions {
+    /// Create new editor options.
+    pub fn new() -> Self {
+        Self {
+            frame_rate: FrameRate::Display,
+            animate: false,
+            min_size: None,
+            max_size: None,
+            aspect_ratio: None,
+            size_step: None,
+            software: false,
+            run_loop: false,
+            fonts: Vec::new(),
+            default_font: None,
+        }
+    }
+
+    /// Set the frame rate while animating.
+    pub fn with_frame_rate(mut self, frame_rate: FrameRate) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Set whether the editor starts out animating.
+    ///
+    /// Animation can be started and stopped at runtime with [`Redraw`](crate::Redraw).
+    pub fn with_animation(mut self, animate: bool) -> Self {
+        self.animate = animate;
+        self
+    }
+
+    /// Set the minimum size of the editor.
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    /// Set the maximum size of the editor.
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Set a fixed aspect ratio of the editor, `width / height`.
+    pub fn with_aspect_ratio(mut self, aspect_ratio: f32) -> Self {
+        self.aspect_ratio = Some(aspect_ratio);
+        self
+    }
+
+    /// Set the steps the size of the editor snaps to.
+    pub fn with_size_step(mut self, width: u32, height: u32) -> Self {
+        self.size_step = Some((width, height));
+        self
+    }
+
+    /// Set whether the editor is rendered on the CPU, even when OpenGL is available.
+    ///
+    /// The editor falls back to CPU rendering automatically when OpenGL is unavailable.
+    pub fn with_software_rendering(mut self, software: bool) -> Self {
+        self.software = software;
+        self
+    }
+
+    /// Set whether the editor runs on the host's run loop on Linux, instead of its own thread.
+    ///
+    /// The editor then handles events on the host's UI thread, through `IRunLoop`. Hosts that
+    /// don't provide a run loop get an editor thread as before.
+    pub fn with_run_loop(mut self, run_loop: bool) -> Self {
+        self.run_loop = run_loop;
+        self
+    }
+
+    /// Add a font embedded in the plugin, for example with `include_bytes!`.
+    ///
+    /// This makes the editor independent of the fonts installed on the system.
+    pub fn with_font(mut self, data: &'static [u8]) -> Self {
+        self.fonts.push(data);
+        self
+    }
+
+    /// Set the default font family.
+    pub fn with_default_font(mut self, family: impl Into<String>) -> Self {
+        self.default_font = Some(family.into());
+        self
+    }
+
+    /// Create the fonts of the editor, with the embedded fonts loaded.
+    pub(crate) fn create_fonts(&self) -> SkiaFonts {
+        let family = self.default_font.as_deref().unwrap_or("Roboto");
+        let mut fonts = SkiaFonts::new(Some(family));
+
+        for data in &self.fonts {
+            fonts.load(FontSource::Data(data.to_vec()));
+        }
+
+        fonts
+    }
+
+    /// Constrain a logical size to the size constraints.
+    pub(crate) fn constrain(&self, width: f32, height: f32) -> (f32, f32) {
+        let (min_width, min_height) = self.min_size.unwrap_or((1, 1));
+        let (min_width, min_height) = (min_width.max(1) as f32, min_height.max(1) as f32);
+
+        let (max_width, max_height) = match self.max_size {
+            Some((width, height)) => (width as f32, height as f32),
+            None => (f32::INFINITY, f32::INFINITY),
+        };
+
+        let (max_width, max_height) = (max_width.max(min_width), max_height.max(min_height));
+
+        let mut width = width.clamp(min_width, max_width);
+        let mut height = height.clamp(min_height, max_height);
+
+        if let Some(ratio) = self.aspect_ratio.filter(|ratio| *ratio > 0.0) {
+            // fit the largest size with the aspect ratio inside the requested size
+            match width / height > ratio {
+                true => width = height * ratio,
+                false => height = width / ratio,
+            }
+
+            if width < min_width {
+                width = min_width;
+                height = width / ratio;
+            }
+
+            if height < min_height {
+                height = min_height;
+                width = height * ratio;
+            }
+
+            // the maximum size wins over the minimum size when both can't be met
+            if width > max_width {
+                width = max_width;
+                height = width / ratio;
+            }
+
+            if height > max_height {
+                height = max_height;
+                width = height * ratio;
+            }
+        }
+
+        if let Some((step_width, step_height)) = self.size_step {
+            width = snap(width, min_width, max_width, step_width as f32);
+            height = snap(height, min_height, max_height, step_height as f32);
+        }
+
+        (
+            width.clamp(min_width, max_width),
+            height.clamp(min_height, max_height),
+        )
+    }
+}
+
+/// Snap `value` to the closest step from `start`, without going past `end`.
+fn snap(value: f32, start: f32, end: f32, step: f32) -> f32 {
+    if step <= 0.0 {
+        return value;
+    }
+
+    let snapped = start + ((value - start) / step).round() * step;
+
+    match snapped > end {
+        true => (snapped - step).max(start),
+        false => snapped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constrain_clamps_to_min_and_max() {
+        let options = EditorOptions::new()
+            .with_min_size(100, 50)
+            .with_max_size(400, 300);
+
+        assert_eq!(options.constrain(10.0, 10.0), (100.0, 50.0));
+        assert_eq!(options.constrain(1000.0, 1000.0), (400.0, 300.0));
+        assert_eq!(options.constrain(200.0, 100.0), (200.0, 100.0));
+    }
+
+    #[test]
+    fn constrain_aspect_ratio_stays_below_max() {
+        let options = EditorOptions::new()
+            .with_min_size(100, 100)
+            .with_max_size(400, 150)
+            .with_aspect_ratio(2.0);
+
+        // the minimum height widens the size, the maximum height narrows it
+        assert_eq!(options.constrain(120.0, 1000.0), (200.0, 100.0));
+        assert_eq!(options.constrain(1000.0, 1000.0), (300.0, 150.0));
+
+        // the minimum width conflicts with the maximum height, the maximum wins
+        let options = EditorOptions::new()
+            .with_min_size(400, 1)
+            .with_max_size(1000, 100)
+            .with_aspect_ratio(1.0);
+
+        assert_eq!(options.constrain(500.0, 500.0), (400.0, 100.0));
+    }
+
+    #[test]
+    fn constrain_aspect_ratio_fits_requested_size() {
+        let options = EditorOptions::new().with_aspect_ratio(2.0);
+
+        assert_eq!(options.constrain(400.0, 400.0), (400.0, 200.0));
+        assert_eq!(options.constrain(400.0, 100.0), (200.0, 100.0));
+    }
+
+    #[test]
+    fn constrain_snaps_to_size_step() {
+        let options = EditorOptions::new()
+            .with_min_size(100, 100)
+            .with_max_size(330, 330)
+            .with_size_step(50, 50);
+
+        assert_eq!(options.constrain(170.0, 130.0), (150.0, 150.0));
+
+        // rounding up would pass the maximum size
+        assert_eq!(options.constrain(329.0, 1000.0), (300.0, 300.0));
+    }
+
+    #[test]
+    fn constrain_snaps_after_aspect_ratio() {
+        let options = EditorOptions::new()
+            .with_min_size(100, 100)
+            .with_max_size(1000, 1000)
+            .with_aspect_ratio(1.0)
+            .with_size_step(100, 100);
+
+        assert_eq!(options.constrain(640.0, 480.0), (500.0, 500.0));
+    }
 }
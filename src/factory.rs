@@ -1,62 +1,164 @@
 #![allow(missing_docs)]
 
-use std::{ffi::c_void, marker::PhantomData};
+use std::{collections::HashSet, ffi::c_void, ptr};
 
+use parking_lot::Mutex;
+
+use uuid::Uuid;
 use vst3_com::IID;
 use vst3_sys::{
     base::{
-        kInvalidArgument, kResultOk, tresult, ClassCardinality, FactoryFlags, IPluginFactory,
-        IPluginFactory2, PClassInfo, PClassInfo2, PFactoryInfo,
+        kInvalidArgument, kResultOk, tresult, IPluginFactory, IPluginFactory2, IPluginFactory3,
+        PClassInfo, PClassInfo2, PClassInfoW, PFactoryInfo,
     },
     VST3,
 };
 
-use crate::{util, RawPlugin, Subcategory, VstPlugin};
+use crate::{
+    class_info, factory_info, util, ClassInfo, FactoryInfo, HostApplication, ModuleInfo, RawPlugin,
+    VstPlugin,
+};
+
+/// A plugin class exported by a [`Factory`].
+#[derive(Clone, Copy, Debug)]
+pub struct PluginClass {
+    class_info: fn() -> ClassInfo,
+    factory_info: fn() -> FactoryInfo,
+    create: fn(Option<HostApplication>) -> *mut c_void,
+}
+
+impl PluginClass {
+    /// Create a new plugin class for `P`.
+    pub fn new<P: VstPlugin>() -> Self {
+        Self {
+            class_info: class_info::<P>,
+            factory_info: factory_info::<P>,
+            create: create_plugin::<P>,
+        }
+    }
+
+    /// Get the class information.
+    pub fn class_info(&self) -> ClassInfo {
+        (self.class_info)()
+    }
+
+    /// Get the factory information.
+    pub fn factory_info(&self) -> FactoryInfo {
+        (self.factory_info)()
+    }
+}
+
+fn create_plugin<P: VstPlugin>(host: Option<HostApplication>) -> *mut c_void {
+    Box::into_raw(RawPlugin::<P>::with_host(host)) as *mut c_void
+}
 
 /// A VST3 plugin factory.
-#[VST3(implements(IPluginFactory, IPluginFactory2))]
-pub struct Factory<P: VstPlugin> {
-    marker: PhantomData<fn() -> P>,
+///
+/// The factory information is taken from the first class.
+#[VST3(implements(IPluginFactory, IPluginFactory2, IPluginFactory3))]
+pub struct Factory {
+    classes: Vec<PluginClass>,
+    host: Mutex<Option<HostApplication>>,
 }
 
-impl<P: VstPlugin> Factory<P> {
+impl Factory {
     /// Create a new plugin factory.
-    pub fn new() -> Box<Self> {
-        Self::allocate(PhantomData)
+    pub fn new(classes: Vec<PluginClass>) -> Box<Self> {
+        Self::allocate(classes, Mutex::new(None))
+    }
+
+    /// Get the classes of the factory.
+    pub fn classes(&self) -> &[PluginClass] {
+        &self.classes
+    }
+
+    /// Find the first uuid shared by more than one class.
+    pub fn duplicate_uuid(&self) -> Option<Uuid> {
+        let mut uuids = HashSet::new();
+
+        for class in &self.classes {
+            let cid = class.class_info().cid;
+
+            if !uuids.insert(cid) {
+                return Some(Uuid::from_bytes_le(cid));
+            }
+        }
+
+        None
+    }
+
+    /// Get the module information of the factory.
+    pub fn module_info(&self, name: impl Into<String>, version: impl Into<String>) -> ModuleInfo {
+        ModuleInfo {
+            name: name.into(),
+            version: version.into(),
+            factory: self.factory_info(),
+            classes: self.classes.iter().map(PluginClass::class_info).collect(),
+        }
+    }
+
+    fn factory_info(&self) -> FactoryInfo {
+        match self.classes.first() {
+            Some(class) => class.factory_info(),
+            None => FactoryInfo::default(),
+        }
+    }
+
+    fn class_info(&self, index: i32) -> Option<ClassInfo> {
+        let index = usize::try_from(index).ok()?;
+        self.classes.get(index).map(PluginClass::class_info)
     }
 }
 
-impl<P: VstPlugin> IPluginFactory for Factory<P> {
+/// Create the factory returned from `GetPluginFactory`.
+///
+/// Returns a null pointer if two classes share a uuid, as hosts can't tell them apart.
+#[doc(hidden)]
+pub fn create_factory(classes: Vec<PluginClass>) -> *mut c_void {
+    let factory = Factory::new(classes);
+
+    if let Some(uuid) = factory.duplicate_uuid() {
+        ori::log::error!("Multiple plugin classes share the uuid {}", uuid);
+        return ptr::null_mut();
+    }
+
+    Box::into_raw(factory) as *mut c_void
+}
+
+impl IPluginFactory for Factory {
     unsafe fn get_factory_info(&self, info: *mut PFactoryInfo) -> tresult {
         if info.is_null() {
             return kInvalidArgument;
         }
 
         let info = &mut *info;
-        let plugin_info = P::info();
-        util::strcpy(&plugin_info.vendor, &mut info.vendor);
-        util::strcpy(&plugin_info.url, &mut info.url);
-        util::strcpy(&plugin_info.email, &mut info.email);
-        info.flags = FactoryFlags::kUnicode as i32 | FactoryFlags::kComponentNonDiscardable as i32;
+        let factory_info = self.factory_info();
+        util::strcpy(&factory_info.vendor, &mut info.vendor);
+        util::strcpy(&factory_info.url, &mut info.url);
+        util::strcpy(&factory_info.email, &mut info.email);
+        info.flags = factory_info.flags;
 
         kResultOk
     }
 
     unsafe fn count_classes(&self) -> i32 {
-        1
+        self.classes.len() as i32
     }
 
     unsafe fn get_class_info(&self, index: i32, info: *mut PClassInfo) -> tresult {
-        if index != 0 || info.is_null() {
+        if info.is_null() {
             return kInvalidArgument;
         }
 
+        let Some(class_info) = self.class_info(index) else {
+            return kInvalidArgument;
+        };
+
         let info = &mut *info;
-        let plugin_info = P::info();
-        util::strcpy(&plugin_info.name, &mut info.name);
-        util::strcpy("Audio Module Class", &mut info.category);
-        info.cid.data = plugin_info.uuid.to_bytes_le();
-        info.cardinality = ClassCardinality::kManyInstances as i32;
+        util::strcpy(&class_info.name, &mut info.name);
+        util::strcpy(&class_info.category, &mut info.category);
+        info.cid.data = class_info.cid;
+        info.cardinality = class_info.cardinality;
 
         kResultOk
     }
@@ -71,44 +173,70 @@ impl<P: VstPlugin> IPluginFactory for Factory<P> {
             return kInvalidArgument;
         }
 
-        let plugin_info = P::info();
+        for class in &self.classes {
+            if (*cid).data == class.class_info().cid {
+                *obj = (class.create)(self.host.lock().clone());
+                return kResultOk;
+            }
+        }
 
-        if (*cid).data != plugin_info.uuid.to_bytes_le() {
+        kInvalidArgument
+    }
+}
+
+impl IPluginFactory2 for Factory {
+    unsafe fn get_class_info2(&self, index: i32, info: *mut PClassInfo2) -> tresult {
+        if info.is_null() {
             return kInvalidArgument;
         }
 
-        let raw_plugin = RawPlugin::<P>::new();
-        *obj = Box::into_raw(raw_plugin) as *mut c_void;
+        let Some(class_info) = self.class_info(index) else {
+            return kInvalidArgument;
+        };
+
+        let info = &mut *info;
+
+        util::strcpy(&class_info.name, &mut info.name);
+        util::strcpy(&class_info.category, &mut info.category);
+        util::strcpy(&class_info.vendor, &mut info.vendor);
+        util::strcpy(&class_info.version, &mut info.version);
+        util::strcpy(&class_info.sdk_version, &mut info.sdk_version);
+        util::strcpy(&class_info.subcategories_string(), &mut info.subcategories);
+        info.cid.data = class_info.cid;
+        info.cardinality = class_info.cardinality;
+        info.class_flags = class_info.class_flags;
 
         kResultOk
     }
 }
 
-impl<P: VstPlugin> IPluginFactory2 for Factory<P> {
-    unsafe fn get_class_info2(&self, index: i32, info: *mut PClassInfo2) -> tresult {
-        if index != 0 || info.is_null() {
+impl IPluginFactory3 for Factory {
+    unsafe fn get_class_info_unicode(&self, index: i32, info: *mut PClassInfoW) -> tresult {
+        if info.is_null() {
             return kInvalidArgument;
         }
 
+        let Some(class_info) = self.class_info(index) else {
+            return kInvalidArgument;
+        };
+
         let info = &mut *info;
-        let plugin_info = P::info();
-
-        let subcategories = plugin_info
-            .subcategories
-            .iter()
-            .map(Subcategory::as_str)
-            .collect::<Vec<_>>()
-            .join("|");
-
-        util::strcpy(&plugin_info.name, &mut info.name);
-        util::strcpy("Audio Module Class", &mut info.category);
-        util::strcpy(&plugin_info.vendor, &mut info.vendor);
-        util::strcpy(&plugin_info.version, &mut info.version);
-        util::strcpy("VST3 3.6.14", &mut info.sdk_version);
-        util::strcpy(&subcategories, &mut info.subcategories);
-        info.cid.data = plugin_info.uuid.to_bytes_le();
-        info.cardinality = ClassCardinality::kManyInstances as i32;
-        info.class_flags = 1 << 1; // kSimpleModeSupported
+
+        util::u16strcpy(&class_info.name, &mut info.name);
+        util::strcpy(&class_info.category, &mut info.category);
+        util::u16strcpy(&class_info.vendor, &mut info.vendor);
+        util::u16strcpy(&class_info.version, &mut info.version);
+        util::u16strcpy(&class_info.sdk_version, &mut info.sdk_version);
+        util::strcpy(&class_info.subcategories_string(), &mut info.subcategories);
+        info.cid.data = class_info.cid;
+        info.cardinality = class_info.cardinality;
+        info.class_flags = class_info.class_flags;
+
+        kResultOk
+    }
+
+    unsafe fn set_host_context(&self, context: *mut c_void) -> tresult {
+        *self.host.lock() = HostApplication::from_context(context);
 
         kResultOk
     }
@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut, RangeInclusive};
 
-use crate::{Param, ParamFlags, Unit};
+use crate::{DefaultParam, Param, ParamFlags, Unit};
 
 /// A floating-point parameter.
 #[derive(Clone, Debug)]
@@ -28,6 +28,9 @@ pub struct Float {
 
     /// The flags of the parameter.
     pub flags: ParamFlags,
+
+    /// Whether the value was set since [`Param::take_changed`].
+    changed: bool,
 }
 
 impl Float {
@@ -42,6 +45,7 @@ impl Float {
             steps: None,
             unit: Unit::Linear,
             flags: ParamFlags::empty(),
+            changed: false,
         }
     }
 
@@ -96,6 +100,18 @@ impl Float {
     }
 }
 
+impl DefaultParam for Float {
+    type Value = f32;
+
+    // the range defaults to `0.0..=1.0` and the default value to the start of the range
+    fn default_param(default: Option<f32>, range: Option<RangeInclusive<f32>>) -> Self {
+        let range = range.unwrap_or(0.0..=1.0);
+        let default = default.unwrap_or(*range.start());
+
+        Self::new(default, range)
+    }
+}
+
 impl Deref for Float {
     type Target = f32;
 
@@ -117,6 +133,7 @@ impl Param for Float {
 
     fn set(&mut self, value: f32) {
         self.value = value;
+        self.changed = true;
     }
 
     fn default(&self) -> f32 {
@@ -145,7 +162,15 @@ impl Param for Float {
         self.unit.clone()
     }
 
+    fn steps(&self) -> Option<i32> {
+        self.steps.map(|steps| steps as i32)
+    }
+
     fn flags(&self) -> ParamFlags {
-        ParamFlags::empty()
+        self.flags
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
     }
 }
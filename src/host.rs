@@ -0,0 +1,261 @@
+use std::{
+    ffi::c_void,
+    mem,
+    ops::Deref,
+    ptr::{self, NonNull},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(target_os = "linux")]
+use std::{
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::OnceLock,
+};
+
+use parking_lot::Mutex;
+use vst3_com::{ComInterface, VstPtr};
+use vst3_sys::{
+    base::{kResultOk, FUnknown},
+    utils::SharedVstPtr,
+    vst::{IConnectionPoint, IHostApplication, IMessage, String128},
+};
+
+use crate::{util, Message};
+
+/// The `IHostApplication` passed to the factory or the plugin by the host.
+#[derive(Clone)]
+pub(crate) struct HostApplication {
+    vst: VstPtr<dyn IHostApplication>,
+}
+
+unsafe impl Send for HostApplication {}
+unsafe impl Sync for HostApplication {}
+
+impl HostApplication {
+    /// Query `IHostApplication` from a host context.
+    ///
+    /// # Safety
+    /// - `context` must be null or a valid `FUnknown`.
+    pub unsafe fn from_context(context: *mut c_void) -> Option<Self> {
+        if context.is_null() {
+            return None;
+        }
+
+        let context: SharedVstPtr<dyn FUnknown> = mem::transmute(context);
+        let vst = context.upgrade()?.cast::<dyn IHostApplication>()?;
+
+        Some(Self { vst })
+    }
+
+    unsafe fn name(&self) -> Option<String> {
+        let mut name: String128 = [0; 128];
+
+        if self.get_name(&mut name) != kResultOk {
+            return None;
+        }
+
+        let len = util::u16strlen(name.as_ptr()).min(name.len());
+        let name = name[..len].iter().map(|&c| c as u16).collect::<Vec<_>>();
+
+        Some(String::from_utf16_lossy(&name))
+    }
+}
+
+impl Deref for HostApplication {
+    type Target = VstPtr<dyn IHostApplication>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.vst
+    }
+}
+
+/// The `IConnectionPoint` of the peer connected to the plugin by the host.
+pub(crate) struct ConnectionPoint {
+    vst: VstPtr<dyn IConnectionPoint>,
+}
+
+unsafe impl Send for ConnectionPoint {}
+
+impl ConnectionPoint {
+    pub fn new(vst: VstPtr<dyn IConnectionPoint>) -> Self {
+        Self { vst }
+    }
+}
+
+/// A handle for requesting a redraw of the editor from any thread.
+///
+/// Requesting a redraw sets a flag and wakes the editor without locking or allocating, so it's
+/// safe to do from the audio thread.
+#[derive(Clone, Debug, Default)]
+pub struct Redraw {
+    requested: Arc<AtomicBool>,
+    animating: Arc<AtomicBool>,
+
+    /// The eventfd waking the editor, created by the first editor.
+    #[cfg(target_os = "linux")]
+    wake_fd: Arc<OnceLock<Option<OwnedFd>>>,
+}
+
+impl Redraw {
+    /// Request a redraw, the editor rebuilds and redraws as soon as it wakes up.
+    pub fn request(&self) {
+        // the editor is already woken by the first request it hasn't taken
+        if !self.requested.swap(true, Ordering::AcqRel) {
+            self.wake();
+        }
+    }
+
+    /// Start redrawing the editor continuously, at the frame rate of the [`EditorOptions`].
+    ///
+    /// [`EditorOptions`]: crate::EditorOptions
+    pub fn start_animation(&self) {
+        if !self.animating.swap(true, Ordering::AcqRel) {
+            self.wake();
+        }
+    }
+
+    /// Stop redrawing the editor continuously.
+    pub fn stop_animation(&self) {
+        self.animating.store(false, Ordering::Release);
+    }
+
+    /// Check if the editor is redrawing continuously.
+    pub fn is_animating(&self) -> bool {
+        self.animating.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::Acquire)
+    }
+
+    /// Wake the editor, if an editor is waiting.
+    pub(crate) fn wake(&self) {
+        #[cfg(target_os = "linux")]
+        if let Some(Some(fd)) = self.wake_fd.get() {
+            let value = 1u64;
+            unsafe { libc::write(fd.as_raw_fd(), &value as *const u64 as *const c_void, 8) };
+        }
+    }
+
+    /// Create the eventfd waking the editor, returns `false` if it couldn't be created.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn init_wake(&self) -> bool {
+        let fd = self.wake_fd.get_or_init(|| unsafe {
+            let fd = libc::eventfd(0, libc::EFD_CLOEXEC);
+            (fd >= 0).then(|| OwnedFd::from_raw_fd(fd))
+        });
+
+        fd.is_some()
+    }
+
+    /// Block until the editor is woken, returns `false` without an eventfd.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn wait(&self) -> bool {
+        let Some(Some(fd)) = self.wake_fd.get() else {
+            return false;
+        };
+
+        let mut value = 0u64;
+
+        loop {
+            let read =
+                unsafe { libc::read(fd.as_raw_fd(), &mut value as *mut u64 as *mut c_void, 8) };
+
+            if read == 8 {
+                return true;
+            }
+
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return false;
+            }
+        }
+    }
+}
+
+/// A handle to the host application.
+///
+/// The handle is passed to [`VstPlugin::activate`](crate::VstPlugin::activate) and is available
+/// as a context in the user interface.
+#[derive(Clone)]
+pub struct Host {
+    inner: Arc<HostInner>,
+}
+
+struct HostInner {
+    application: Mutex<Option<HostApplication>>,
+    name: Mutex<Option<String>>,
+    peer: Mutex<Option<ConnectionPoint>>,
+    redraw: Redraw,
+}
+
+impl Host {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::new(HostInner {
+                application: Mutex::new(None),
+                name: Mutex::new(None),
+                peer: Mutex::new(None),
+                redraw: Redraw::default(),
+            }),
+        }
+    }
+
+    pub(crate) fn set_application(&self, application: Option<HostApplication>) {
+        let name = application.as_ref().and_then(|app| unsafe { app.name() });
+
+        *self.inner.name.lock() = name;
+        *self.inner.application.lock() = application;
+    }
+
+    pub(crate) fn set_peer(&self, peer: Option<ConnectionPoint>) {
+        *self.inner.peer.lock() = peer;
+    }
+
+    /// Get the name of the host, eg. `"REAPER"`.
+    ///
+    /// VST3 hosts don't report their version, the name is all there is to identify them.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name.lock().clone()
+    }
+
+    /// Get a handle for requesting a redraw of the editor.
+    pub fn redraw(&self) -> Redraw {
+        self.inner.redraw.clone()
+    }
+
+    /// Create a new message with `id`, allocated by the host.
+    pub fn create_message(&self, id: &str) -> Option<Message> {
+        let application = self.inner.application.lock();
+        let application = application.as_ref()?;
+
+        unsafe {
+            let mut cid = <dyn IMessage as ComInterface>::IID;
+            let mut iid = <dyn IMessage as ComInterface>::IID;
+            let mut obj = ptr::null_mut();
+
+            if application.create_instance(&mut cid, &mut iid, &mut obj) != kResultOk {
+                return None;
+            }
+
+            let message = Message::new(VstPtr::owned(NonNull::new(obj)?.cast()));
+            message.set_id(id);
+
+            Some(message)
+        }
+    }
+
+    /// Send a message to the peer the host connected the plugin to.
+    ///
+    /// Returns `false` if the plugin isn't connected or the peer didn't accept the message.
+    pub fn send(&self, message: &Message) -> bool {
+        let peer = self.inner.peer.lock();
+
+        match peer.as_ref() {
+            Some(peer) => unsafe { peer.vst.notify(message.as_shared()) == kResultOk },
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use ori::core::image::Image;
+use resvg::{tiny_skia, usvg};
+
+/// Decode an image embedded in the plugin, like a PNG or JPEG.
+///
+/// See [`include_image!`](crate::include_image) for embedding an image file.
+pub fn load_image(data: &[u8]) -> Option<Image> {
+    Image::try_load_data(data.to_vec()).ok()
+}
+
+/// Render an SVG embedded in the plugin, `scale` is the ratio of pixels to SVG units.
+///
+/// See [`include_svg!`](crate::include_svg) for embedding an SVG file.
+pub fn load_svg(data: &[u8], scale: f32) -> Option<Image> {
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default()).ok()?;
+
+    let size = tree.size().to_int_size().scale_by(scale)?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+
+    let transform = tiny_skia::Transform::from_scale(scale, scale);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // the pixels of a pixmap are premultiplied
+    let pixels = pixmap.pixels().iter().flat_map(|pixel| {
+        let color = pixel.demultiply();
+        [color.red(), color.green(), color.blue(), color.alpha()]
+    });
+
+    Some(Image::new(pixels.collect(), size.width(), size.height()))
+}
+
+/// Embed an image file in the plugin and decode it.
+///
+/// Panics if the image can't be decoded.
+///
+/// ```ignore
+/// let knob = include_image!("../assets/knob.png");
+/// ```
+#[macro_export]
+macro_rules! include_image {
+    ($path:literal) => {
+        $crate::load_image(::std::include_bytes!($path)).expect(::std::concat!(
+            "failed to decode image `",
+            $path,
+            "`"
+        ))
+    };
+}
+
+/// Embed an SVG file in the plugin and render it, at an optional scale.
+///
+/// Panics if the SVG can't be rendered.
+///
+/// ```ignore
+/// let logo = include_svg!("../assets/logo.svg", 2.0);
+/// ```
+#[macro_export]
+macro_rules! include_svg {
+    ($path:literal) => {
+        $crate::include_svg!($path, 1.0)
+    };
+    ($path:literal, $scale:expr) => {
+        $crate::load_svg(::std::include_bytes!($path), $scale).expect(::std::concat!(
+            "failed to render svg `",
+            $path,
+            "`"
+        ))
+    };
+}
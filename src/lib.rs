@@ -5,14 +5,24 @@
 
 mod audio_layout;
 mod buffer;
+mod bypass;
+mod channel;
 mod component;
 mod controller;
 mod editor;
 mod factory;
 mod float;
+mod host;
+#[cfg(feature = "image")]
+mod image;
+mod message;
+mod module_info;
 mod param;
 mod plugin;
+mod preview;
 mod processor;
+mod raster;
+mod snapshot;
 mod state;
 mod unit;
 mod util;
@@ -25,10 +35,20 @@ pub use ori::*;
 
 pub use audio_layout::*;
 pub use buffer::*;
+use bypass::*;
+pub use channel::*;
+pub use editor::{EditorOptions, FileDrop, FrameRate};
 pub use factory::*;
 pub use float::*;
+pub use host::*;
+#[cfg(feature = "image")]
+pub use image::*;
+pub use message::*;
+pub use module_info::*;
 pub use param::*;
 pub use plugin::*;
+pub use preview::*;
+pub use snapshot::*;
 use state::*;
 use view::*;
 
@@ -43,17 +63,21 @@ pub fn install_log() {
     let _ = ori::log::subscriber::set_global_default(layer.finish());
 }
 
-/// Macro for exporting a [`VstPlugin`] and generating the necessary boilerplate.
+/// Macro for exporting one or more [`VstPlugin`]s and generating the necessary boilerplate.
+///
+/// ```ignore
+/// ori_vst::export!(Compressor, Limiter, Gate);
+/// ```
 #[macro_export]
 macro_rules! export {
-    ($plugin:ty) => {
+    ($($plugin:ty),+ $(,)?) => {
         #[doc(hidden)]
         const _: () = {
             #[no_mangle]
             unsafe extern "system" fn GetPluginFactory() -> *mut ::std::ffi::c_void {
-                let factory = $crate::Factory::<$plugin>::new();
-
-                ::std::boxed::Box::into_raw(factory) as *mut ::std::ffi::c_void
+                $crate::create_factory(::std::vec![
+                    $($crate::PluginClass::new::<$plugin>()),+
+                ])
             }
 
             #[no_mangle]
@@ -105,8 +129,9 @@ pub mod prelude {
     //! A prelude for convenience.
 
     pub use crate::{
-        Activate, AudioLayout, AudioPort, Bool, Buffer, BufferLayout, Float, Info, Param,
-        ParamFlags, Params, Process, Subcategory, Unit, VstPlugin,
+        Activate, AudioLayout, AudioPort, Bool, Buffer, BufferLayout, EditorOptions, FileDrop,
+        Float, FrameRate, Host, Info, Message, Param, ParamFlags, Params, Process, Subcategory,
+        Unit, VstPlugin,
     };
 
     pub use ori_vst_macro::uuid;
@@ -0,0 +1,198 @@
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    mem, ptr, slice,
+};
+
+use vst3_com::VstPtr;
+use vst3_sys::{
+    base::{kInvalidArgument, kResultFalse, kResultOk, tresult},
+    utils::SharedVstPtr,
+    vst::{IAttributeList, IComponent, IConnectionPoint, IMessage, TChar},
+};
+
+use crate::{util, ConnectionPoint, RawPlugin, VstPlugin};
+
+/// A message sent between the plugin and its peer, see [`Host::create_message`](crate::Host).
+///
+/// Messages carry a set of attributes identified by string keys.
+pub struct Message {
+    vst: VstPtr<dyn IMessage>,
+}
+
+unsafe impl Send for Message {}
+
+impl Message {
+    pub(crate) fn new(vst: VstPtr<dyn IMessage>) -> Self {
+        Self { vst }
+    }
+
+    pub(crate) fn as_shared(&self) -> SharedVstPtr<dyn IMessage> {
+        unsafe { mem::transmute(self.vst.as_raw()) }
+    }
+
+    /// Get the id of the message.
+    pub fn id(&self) -> String {
+        unsafe {
+            let id = self.vst.get_message_id();
+
+            if id.is_null() {
+                return String::new();
+            }
+
+            CStr::from_ptr(id).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Set the id of the message.
+    pub fn set_id(&self, id: &str) {
+        let Ok(id) = CString::new(id) else {
+            return;
+        };
+
+        // the message copies the id
+        unsafe { self.vst.set_message_id(id.as_ptr()) };
+    }
+
+    /// Set an integer attribute.
+    pub fn set_int(&self, key: &str, value: i64) -> bool {
+        self.with_attributes(key, |attributes, key| unsafe {
+            attributes.set_int(key, value) == kResultOk
+        })
+        .unwrap_or(false)
+    }
+
+    /// Get an integer attribute.
+    pub fn int(&self, key: &str) -> Option<i64> {
+        self.with_attributes(key, |attributes, key| unsafe {
+            let mut value = 0;
+
+            match attributes.get_int(key, &mut value) {
+                kResultOk => Some(value),
+                _ => None,
+            }
+        })?
+    }
+
+    /// Set a float attribute.
+    pub fn set_float(&self, key: &str, value: f64) -> bool {
+        self.with_attributes(key, |attributes, key| unsafe {
+            attributes.set_float(key, value) == kResultOk
+        })
+        .unwrap_or(false)
+    }
+
+    /// Get a float attribute.
+    pub fn float(&self, key: &str) -> Option<f64> {
+        self.with_attributes(key, |attributes, key| unsafe {
+            let mut value = 0.0;
+
+            match attributes.get_float(key, &mut value) {
+                kResultOk => Some(value),
+                _ => None,
+            }
+        })?
+    }
+
+    /// Set a string attribute.
+    pub fn set_string(&self, key: &str, value: &str) -> bool {
+        let mut string = vec![0; value.encode_utf16().count() + 1];
+        unsafe { util::u16strcpy(value, &mut string) };
+
+        self.with_attributes(key, |attributes, key| unsafe {
+            attributes.set_string(key, string.as_ptr()) == kResultOk
+        })
+        .unwrap_or(false)
+    }
+
+    /// Get a string attribute.
+    pub fn string(&self, key: &str) -> Option<String> {
+        self.with_attributes(key, |attributes, key| unsafe {
+            let mut string = [0 as TChar; 1024];
+            let size = mem::size_of_val(&string) as u32;
+
+            if attributes.get_string(key, string.as_mut_ptr(), size) != kResultOk {
+                return None;
+            }
+
+            let len = util::u16strlen(string.as_ptr()).min(string.len());
+            let string = string[..len].iter().map(|&c| c as u16).collect::<Vec<_>>();
+
+            Some(String::from_utf16_lossy(&string))
+        })?
+    }
+
+    /// Set a binary attribute.
+    pub fn set_binary(&self, key: &str, data: &[u8]) -> bool {
+        self.with_attributes(key, |attributes, key| unsafe {
+            let size = data.len() as u32;
+            attributes.set_binary(key, data.as_ptr() as *const c_void, size) == kResultOk
+        })
+        .unwrap_or(false)
+    }
+
+    /// Get a binary attribute.
+    pub fn binary(&self, key: &str) -> Option<Vec<u8>> {
+        self.with_attributes(key, |attributes, key| unsafe {
+            let mut data = ptr::null();
+            let mut size = 0;
+
+            if attributes.get_binary(key, &mut data, &mut size) != kResultOk || data.is_null() {
+                return None;
+            }
+
+            Some(slice::from_raw_parts(data as *const u8, size as usize).to_vec())
+        })?
+    }
+
+    fn with_attributes<T>(
+        &self,
+        key: &str,
+        f: impl FnOnce(&VstPtr<dyn IAttributeList>, *const c_char) -> T,
+    ) -> Option<T> {
+        let key = CString::new(key).ok()?;
+        let attributes = unsafe { self.vst.get_attributes().upgrade()? };
+
+        Some(f(&attributes, key.as_ptr()))
+    }
+}
+
+impl<P: VstPlugin> IConnectionPoint for RawPlugin<P> {
+    unsafe fn connect(&self, other: SharedVstPtr<dyn IConnectionPoint>) -> tresult {
+        let Some(other) = other.upgrade() else {
+            return kInvalidArgument;
+        };
+
+        // the plugin is both component and controller, connected to itself a message sent
+        // while the plugin is locked would deadlock in `notify`
+        if let Some(component) = other.cast::<dyn IComponent>() {
+            if component.as_raw() as *const c_void == self as *const Self as *const c_void {
+                return kResultFalse;
+            }
+        }
+
+        self.state.host.set_peer(Some(ConnectionPoint::new(other)));
+
+        kResultOk
+    }
+
+    unsafe fn disconnect(&self, _other: SharedVstPtr<dyn IConnectionPoint>) -> tresult {
+        self.state.host.set_peer(None);
+
+        kResultOk
+    }
+
+    unsafe fn notify(&self, message: SharedVstPtr<dyn IMessage>) -> tresult {
+        let Some(message) = message.upgrade() else {
+            return kInvalidArgument;
+        };
+
+        let message = Message::new(message);
+
+        let mut plugin = self.state.plugin.lock();
+
+        match plugin.message(&self.state.host, &message) {
+            true => kResultOk,
+            false => kResultFalse,
+        }
+    }
+}
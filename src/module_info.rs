@@ -0,0 +1,54 @@
+use vst3_sys::base::{ClassCardinality, FactoryFlags};
+
+pub use ori_vst_module_info::{
+    ClassInfo, FactoryInfo, ModuleInfo, AUDIO_MODULE_CLASS, SDK_VERSION,
+};
+
+use crate::{Subcategory, VstPlugin};
+
+/// Get the factory information of a plugin.
+pub fn factory_info<P: VstPlugin>() -> FactoryInfo {
+    let info = P::info();
+
+    FactoryInfo {
+        vendor: info.vendor,
+        url: info.url,
+        email: info.email,
+        flags: FactoryFlags::kUnicode as i32 | FactoryFlags::kComponentNonDiscardable as i32,
+    }
+}
+
+/// Get the class information of a plugin.
+pub fn class_info<P: VstPlugin>() -> ClassInfo {
+    let info = P::info();
+
+    ClassInfo {
+        cid: info.uuid.to_bytes_le(),
+        category: String::from(AUDIO_MODULE_CLASS),
+        name: info.name,
+        vendor: info.vendor,
+        version: info.version,
+        sdk_version: String::from(SDK_VERSION),
+        subcategories: info
+            .subcategories
+            .iter()
+            .map(Subcategory::as_str)
+            .map(String::from)
+            .collect(),
+        class_flags: 1 << 1, // kSimpleModeSupported
+        cardinality: ClassCardinality::kManyInstances as i32,
+    }
+}
+
+/// Get the module information of a plugin.
+pub fn module_info<P: VstPlugin>(
+    name: impl Into<String>,
+    version: impl Into<String>,
+) -> ModuleInfo {
+    ModuleInfo {
+        name: name.into(),
+        version: version.into(),
+        factory: factory_info::<P>(),
+        classes: vec![class_info::<P>()],
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, RangeInclusive};
 
 pub use ori_vst_macro::Params;
 
@@ -59,6 +59,21 @@ pub trait Param {
     fn from_string(&self, string: &str) -> f32 {
         string.parse().unwrap_or_default()
     }
+
+    /// Check whether [`Param::set`] was called since the last call, clearing the change.
+    fn take_changed(&mut self) -> bool {
+        false
+    }
+}
+
+/// A parameter that `#[derive(Params)]` can construct from its `default` and `range` attributes,
+/// when the struct is marked with `#[params(default)]`.
+pub trait DefaultParam: Param {
+    /// The type of the `default` attribute.
+    type Value;
+
+    /// Create the parameter, `None` if the attribute is missing.
+    fn default_param(default: Option<Self::Value>, range: Option<RangeInclusive<f32>>) -> Self;
 }
 
 /// A collection of parameters.
@@ -74,8 +89,20 @@ pub trait Params {
 
     /// Get the identifier of a parameter.
     ///
-    /// This is a unique string that identifies the parameter.
+    /// This is a unique string that identifies the parameter in the saved state, hosts identify
+    /// parameters by their index.
     fn identifier(&self, index: usize) -> Option<String>;
+
+    /// Call `f` with the index of every parameter changed since the last call.
+    fn take_changed(&mut self, f: &mut dyn FnMut(usize)) {
+        for index in 0..self.count() {
+            if let Some(param) = self.param(index) {
+                if param.take_changed() {
+                    f(index);
+                }
+            }
+        }
+    }
 }
 
 impl Params for () {
@@ -110,6 +137,12 @@ pub struct Bool {
 
     /// The short name of the parameter.
     pub short: Option<String>,
+
+    /// The flags of the parameter.
+    pub flags: ParamFlags,
+
+    /// Whether the value was set since [`Param::take_changed`].
+    changed: bool,
 }
 
 impl Bool {
@@ -120,6 +153,8 @@ impl Bool {
             default,
             name: None,
             short: None,
+            flags: ParamFlags::empty(),
+            changed: false,
         }
     }
 
@@ -134,6 +169,20 @@ impl Bool {
         self.short = Some(short.into());
         self
     }
+
+    /// Set the flags for the parameter.
+    pub fn flags(mut self, flags: ParamFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+}
+
+impl DefaultParam for Bool {
+    type Value = bool;
+
+    fn default_param(default: Option<bool>, _range: Option<RangeInclusive<f32>>) -> Self {
+        Self::new(default.unwrap_or(false))
+    }
 }
 
 impl Param for Bool {
@@ -143,6 +192,7 @@ impl Param for Bool {
 
     fn set(&mut self, plain: f32) {
         self.value = plain > 0.5;
+        self.changed = true;
     }
 
     fn default(&self) -> f32 {
@@ -177,6 +227,10 @@ impl Param for Bool {
         Some(1)
     }
 
+    fn flags(&self) -> ParamFlags {
+        self.flags
+    }
+
     fn to_string(&self, plain: f32) -> String {
         format!("{}", plain > 0.5)
     }
@@ -188,6 +242,10 @@ impl Param for Bool {
             _ => self.get(),
         }
     }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
 }
 
 impl Deref for Bool {
@@ -260,6 +318,16 @@ macro_rules! impl_iterator {
 
                 None
             }
+
+            fn take_changed(&mut self, f: &mut dyn FnMut(usize)) {
+                let mut count = 0;
+
+                for params in self {
+                    let params_count = params.count();
+                    params.take_changed(&mut |index| f(count + index));
+                    count += params_count;
+                }
+            }
         }
     };
 }
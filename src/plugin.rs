@@ -1,16 +1,19 @@
 #![allow(missing_docs)]
 
-use std::{ffi::c_void, ptr::NonNull, sync::Arc};
+use std::{ffi::c_void, sync::Arc};
 
 use ori::core::{view::View, window::Window};
 use uuid::Uuid;
 use vst3_sys::{
     base::{kResultOk, tresult, IPluginBase},
-    vst::{IAudioProcessor, IComponent, IEditController},
+    vst::{IAudioProcessor, IComponent, IConnectionPoint, IEditController},
     VST3,
 };
 
-use crate::{AudioLayout, Buffer, BufferLayout, Params, PluginState};
+use crate::{
+    AudioLayout, Buffer, BufferLayout, EditorOptions, Host, HostApplication, Message, Params,
+    PluginState,
+};
 
 /// A VST3 plugin.
 pub trait VstPlugin: Sized + Send + 'static {
@@ -28,9 +31,12 @@ pub trait VstPlugin: Sized + Send + 'static {
     /// Create a new instance of the plugin.
     fn new() -> Self;
 
-    /// Get the parameters of the plugin.
+    /// Get the parameters of the plugin, none by default.
+    ///
+    /// Plugins deriving [`Params`] usually return `self`.
     fn params(&mut self) -> &mut dyn Params {
-        unsafe { &mut *NonNull::<()>::dangling().as_ptr() }
+        // `()` is zero-sized, so leaking it doesn't allocate
+        Box::leak(Box::new(()))
     }
 
     /// Create a new window.
@@ -38,14 +44,24 @@ pub trait VstPlugin: Sized + Send + 'static {
         Window::new()
     }
 
+    /// Get the options of the editor.
+    fn editor() -> EditorOptions {
+        EditorOptions::new()
+    }
+
     /// Build the user interface of the plugin.
     fn ui(&mut self) -> impl View<Self> + 'static;
 
     /// Activate the plugin is activated.
     ///
     /// This allows the plugin to allocate any resources it needs.
-    fn activate(&mut self, audio_layout: &AudioLayout, buffer_layout: &BufferLayout) -> Activate {
-        let _ = (audio_layout, buffer_layout);
+    fn activate(
+        &mut self,
+        host: &Host,
+        audio_layout: &AudioLayout,
+        buffer_layout: &BufferLayout,
+    ) -> Activate {
+        let _ = (host, audio_layout, buffer_layout);
 
         Activate::new()
     }
@@ -53,6 +69,23 @@ pub trait VstPlugin: Sized + Send + 'static {
     /// Deactivate the plugin.
     fn deactivate(&mut self) {}
 
+    /// Handle a message sent by the peer the host connected the plugin to.
+    ///
+    /// Returns `true` if the message was handled.
+    fn message(&mut self, host: &Host, message: &Message) -> bool {
+        let _ = (host, message);
+
+        false
+    }
+
+    /// Handle a change of the parameter at `index`.
+    ///
+    /// This is called on the audio thread before [`VstPlugin::process`], for every parameter set
+    /// by the host, the state or the editor since the last block.
+    fn param_changed(&mut self, index: usize) {
+        let _ = index;
+    }
+
     /// Reset the processing state of the plugin.
     fn reset(&mut self) {}
 
@@ -210,7 +243,7 @@ impl Subcategory {
 /// A raw wrapper around a VST3 plugin.
 ///
 /// This should never be used directly.
-#[VST3(implements(IComponent, IEditController, IAudioProcessor))]
+#[VST3(implements(IComponent, IEditController, IAudioProcessor, IConnectionPoint))]
 pub struct RawPlugin<P: VstPlugin> {
     /// The state of the plugin.
     pub(crate) state: Arc<PluginState<P>>,
@@ -221,10 +254,22 @@ impl<P: VstPlugin> RawPlugin<P> {
     pub fn new() -> Box<Self> {
         Self::allocate(Arc::new(PluginState::new()))
     }
+
+    /// Create a new raw plugin with the host context of the factory.
+    pub(crate) fn with_host(host: Option<HostApplication>) -> Box<Self> {
+        let state = PluginState::new();
+        state.set_host(host);
+
+        Self::allocate(Arc::new(state))
+    }
 }
 
 impl<P: VstPlugin> IPluginBase for RawPlugin<P> {
-    unsafe fn initialize(&self, _context: *mut c_void) -> tresult {
+    unsafe fn initialize(&self, context: *mut c_void) -> tresult {
+        if let Some(host) = HostApplication::from_context(context) {
+            self.state.set_host(Some(host));
+        }
+
         kResultOk
     }
 
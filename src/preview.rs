@@ -0,0 +1,232 @@
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "wav")]
+use std::{io, path::Path};
+
+use ori::prelude::*;
+
+use crate::{Buffer, BufferLayout, Params, PluginState, Process, ProcessMode, VstPlugin};
+
+/// The width of the parameter panel of the preview.
+const PANEL_WIDTH: u32 = 240;
+
+/// Runs the editor of a plugin in a standalone window, for developing the user interface.
+///
+/// The window shows the editor next to a panel with a slider for every parameter, while an
+/// audio thread calls [`VstPlugin::process`] with silence, or with the input set with
+/// [`Preview::with_input`] or read with `Preview::with_input_file`, behind the `wav` feature.
+///
+/// ```ignore
+/// fn main() {
+///     Preview::new().run::<Gain>();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Preview {
+    /// The sample rate of the audio thread.
+    pub sample_rate: f32,
+
+    /// The number of samples processed at a time.
+    pub block_size: usize,
+
+    /// The input channels, looped while the preview runs.
+    pub input: Vec<Vec<f32>>,
+}
+
+impl Default for Preview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Preview {
+    /// Create a new preview.
+    pub fn new() -> Self {
+        Self {
+            sample_rate: 44100.0,
+            block_size: 512,
+            input: Vec::new(),
+        }
+    }
+
+    /// Set the sample rate of the audio thread.
+    pub fn with_sample_rate(mut self, sample_rate: f32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Set the number of samples processed at a time.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Set the input channels, for example read from an audio file.
+    pub fn with_input(mut self, input: Vec<Vec<f32>>) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Set the input channels and the sample rate from a WAV file.
+    #[cfg(feature = "wav")]
+    pub fn with_input_file(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        use hound::{SampleFormat, WavReader};
+
+        let mut reader = WavReader::open(path).map_err(io::Error::other)?;
+        let spec = reader.spec();
+
+        let samples: Vec<f32> = match spec.sample_format {
+            SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+            SampleFormat::Int => {
+                let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 / scale))
+                    .collect::<Result<_, _>>()
+            }
+        }
+        .map_err(io::Error::other)?;
+
+        let channels = usize::from(spec.channels).max(1);
+        let mut input = vec![Vec::with_capacity(samples.len() / channels); channels];
+
+        for frame in samples.chunks_exact(channels) {
+            for (channel, sample) in input.iter_mut().zip(frame) {
+                channel.push(*sample);
+            }
+        }
+
+        self.sample_rate = spec.sample_rate as f32;
+        Ok(self.with_input(input))
+    }
+
+    /// Open the preview window and run the audio thread.
+    ///
+    /// This blocks until the window is closed, then deactivates the plugin and returns.
+    pub fn run<P: VstPlugin>(self) {
+        let state = Arc::new(PluginState::<P>::new());
+
+        state.set_buffer_layout(Some(BufferLayout {
+            sample_rate: self.sample_rate,
+            max_buffer_size: self.block_size,
+            mode: ProcessMode::Realtime,
+        }));
+
+        state.activate(&mut state.plugin.lock());
+
+        let window = P::window();
+        let window = window.size(window.width() + PANEL_WIDTH, window.height());
+
+        #[cfg(target_os = "linux")]
+        let editor = unsafe { crate::x11::spawn_preview(state.clone(), window, preview_ui::<P>) };
+
+        #[cfg(target_os = "linux")]
+        let closed = || editor.is_finished();
+
+        #[cfg(not(target_os = "linux"))]
+        let closed = {
+            let _ = window;
+            || false
+        };
+
+        self.process(&state, closed);
+
+        #[cfg(target_os = "linux")]
+        let _ = editor.join();
+
+        state.plugin.lock().deactivate();
+    }
+
+    /// Process audio on the current thread until `closed` returns true.
+    fn process<P: VstPlugin>(&self, state: &PluginState<P>, closed: impl Fn() -> bool) {
+        let audio_layout = state.audio_layout();
+        let buffer_layout = state.buffer_layout().unwrap();
+
+        let inputs = audio_layout.input.as_ref().map_or(0, |port| port.channels) as usize;
+        let outputs = audio_layout.output.as_ref().map_or(0, |port| port.channels) as usize;
+
+        let samples = self.block_size;
+        let block = Duration::from_secs_f32(samples as f32 / self.sample_rate);
+
+        let mut channels = vec![vec![0.0; samples]; usize::max(inputs, outputs)];
+        let mut position = 0;
+
+        while !closed() {
+            let start = Instant::now();
+
+            for (i, channel) in channels.iter_mut().enumerate() {
+                match self
+                    .input
+                    .get(i)
+                    .filter(|input| i < inputs && !input.is_empty())
+                {
+                    Some(input) => {
+                        for (j, sample) in channel.iter_mut().enumerate() {
+                            *sample = input[(position + j) % input.len()];
+                        }
+                    }
+                    None => channel.fill(0.0),
+                }
+            }
+
+            position += samples;
+
+            {
+                let mut plugin = state.plugin.lock();
+                let mut bypass = state.bypass.lock();
+
+                let mut slices: Vec<_> = channels.iter_mut().map(Vec::as_mut_slice).collect();
+                let mut buffer = Buffer::new(samples, &mut slices);
+
+                state.notify_changed_params(&mut plugin);
+                bypass.capture(&buffer, inputs);
+
+                let status = match bypass.is_processing() {
+                    true => plugin.process(&mut buffer, &mut [], buffer_layout.clone()),
+                    false => Process::Tail(state.latency()),
+                };
+
+                bypass.mix(&mut buffer);
+                state.set_status(status);
+            }
+
+            thread::sleep(block.saturating_sub(start.elapsed()));
+        }
+    }
+}
+
+/// The editor of the plugin, next to a panel with the parameters.
+fn preview_ui<P: VstPlugin>(plugin: &mut P) -> Box<dyn AnyView<P>> {
+    let params = plugin.params();
+    let mut rows = Vec::new();
+
+    for index in 0..params.count() {
+        let (Some(info), Some(param)) = (params.info(index), params.param(index)) else {
+            continue;
+        };
+
+        let value = param.get();
+        let label = text(format!("{}: {}", info.name, param.to_string(value)));
+
+        let slider = slider(param.normalize(value)).range(0.0..=1.0).on_input(
+            move |cx, plugin: &mut P, normalized| {
+                if let Some(param) = plugin.params().param(index) {
+                    param.set(param.plain(normalized));
+                }
+
+                cx.rebuild();
+            },
+        );
+
+        rows.push(vstack![label, slider]);
+    }
+
+    let panel = width(PANEL_WIDTH as f32, vscroll(vstack(rows)));
+
+    any(hstack![panel, plugin.ui()])
+}
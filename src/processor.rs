@@ -3,13 +3,16 @@ use std::{ptr, slice};
 use vst3_sys::{
     base::{kInvalidArgument, kResultFalse, kResultOk, tresult, TBool},
     vst::{
-        k50, k51, k70Cine, k71Cine, kEmpty, kMono, kStereo, AudioBusBuffers, BusDirection,
-        BusDirections, IAudioProcessor, ProcessData, ProcessModes, ProcessSetup,
-        SpeakerArrangement, SymbolicSampleSizes,
+        AudioBusBuffers, BusDirection, BusDirections, IAudioProcessor, IParamValueQueue,
+        IParameterChanges, ProcessData, ProcessModes, ProcessSetup, SpeakerArrangement,
+        SymbolicSampleSizes,
     },
 };
 
-use crate::{Buffer, BufferLayout, Process, ProcessMode, RawPlugin, VstPlugin};
+use crate::{
+    speaker_arrangement, Buffer, BufferLayout, OutputParam, Params, PluginState, Process,
+    ProcessMode, RawPlugin, VstPlugin,
+};
 
 const K_INPUT: i32 = BusDirections::kInput as i32;
 const K_OUTPUT: i32 = BusDirections::kOutput as i32;
@@ -18,19 +21,6 @@ const K_REALTIME: i32 = ProcessModes::kRealtime as i32;
 const K_PREFETCH: i32 = ProcessModes::kPrefetch as i32;
 const K_OFFLINE: i32 = ProcessModes::kOffline as i32;
 
-fn channel_count(channels: u32) -> u64 {
-    match channels {
-        0 => kEmpty,
-        1 => kMono,
-        2 => kStereo,
-        5 => k50,
-        6 => k51,
-        7 => k70Cine,
-        8 => k71Cine,
-        n => (1 << n) - 1,
-    }
-}
-
 impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
     unsafe fn set_bus_arrangements(
         &self,
@@ -47,11 +37,11 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
         let mut outputs = Vec::with_capacity(num_outs as usize);
 
         for i in 0..num_ins {
-            inputs.push(*inputs_ptr.add(i as usize) as u32);
+            inputs.push((*inputs_ptr.add(i as usize)).count_ones());
         }
 
         for i in 0..num_outs {
-            outputs.push(*outputs_ptr.add(i as usize) as u32);
+            outputs.push((*outputs_ptr.add(i as usize)).count_ones());
         }
 
         match P::layout(&inputs, &outputs) {
@@ -74,7 +64,7 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
         match dir {
             K_INPUT => {
                 if let Some(port) = layout.input_port(index as u32) {
-                    *arr = channel_count(port.channels);
+                    *arr = speaker_arrangement(port.channels);
 
                     kResultOk
                 } else {
@@ -83,7 +73,7 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
             }
             K_OUTPUT => {
                 if let Some(port) = layout.output_port(index as u32) {
-                    *arr = channel_count(port.channels);
+                    *arr = speaker_arrangement(port.channels);
 
                     kResultOk
                 } else {
@@ -151,6 +141,14 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
 
         let samples = data.num_samples as usize;
 
+        let mut plugin = self.state.plugin.lock();
+        let mut bypass = self.state.bypass.lock();
+
+        let automated =
+            apply_param_changes(data, &mut bypass.with_params(plugin.params()), &self.state);
+
+        self.state.notify_changed_params(&mut plugin);
+
         if is_param_flush(data) {
             return kResultOk;
         }
@@ -215,10 +213,23 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
             update_buffer(&mut aux_buffers[i], samples, input, output);
         }
 
-        let mut plugin = self.state.plugin.lock();
-        let status = plugin.process(main_buffer, aux_buffers, buffer_layout);
+        let inputs = audio_layout.input.as_ref().map_or(0, |port| port.channels);
+        bypass.capture(main_buffer, inputs as usize);
+
+        let status = match bypass.is_processing() {
+            true => plugin.process(main_buffer, aux_buffers, buffer_layout),
+            false => Process::Tail(self.state.latency()),
+        };
+
+        bypass.mix(main_buffer);
         self.state.set_status(status);
 
+        let output_params = &mut self.state.output_params.lock();
+        if write_output_params(data, plugin.params(), output_params) || automated {
+            // requesting a redraw doesn't lock or allocate, unlike rebuilding the editor
+            self.state.host.redraw().request();
+        }
+
         kResultOk
     }
 
@@ -231,6 +242,89 @@ impl<P: VstPlugin> IAudioProcessor for RawPlugin<P> {
     }
 }
 
+/// Set the parameters to the last value of their queue in the input parameter changes.
+///
+/// Changes are applied once per block, so automation isn't sample accurate.
+///
+/// Returns true if any parameter was set.
+unsafe fn apply_param_changes<P: VstPlugin>(
+    data: &ProcessData,
+    params: &mut dyn Params,
+    state: &PluginState<P>,
+) -> bool {
+    let Some(changes) = data.input_param_changes.upgrade() else {
+        return false;
+    };
+
+    let mut applied = false;
+
+    for i in 0..changes.get_parameter_count() {
+        let Some(queue) = changes.get_parameter_data(i).upgrade() else {
+            continue;
+        };
+
+        let mut offset = 0;
+        let mut value = 0.0;
+
+        let last = queue.get_point_count() - 1;
+        if last < 0 || queue.get_point(last, &mut offset, &mut value) != kResultOk {
+            continue;
+        }
+
+        let index = queue.get_parameter_id() as usize;
+
+        if let Some(param) = params.param(index) {
+            let plain = param.plain(value as f32);
+            param.set(plain);
+            state.set_by_host(index);
+            applied = true;
+        }
+    }
+
+    applied
+}
+
+/// Write the changed read-only parameters to the output parameter changes.
+///
+/// Returns true if any parameter changed.
+unsafe fn write_output_params(
+    data: &ProcessData,
+    params: &mut dyn Params,
+    output_params: &mut [OutputParam],
+) -> bool {
+    let changes = data.output_param_changes.upgrade();
+    let mut changed = false;
+
+    for output in output_params {
+        let Some(param) = params.param(output.index) else {
+            continue;
+        };
+
+        let value = param.get();
+
+        if value == output.value {
+            continue;
+        }
+
+        output.value = value;
+        changed = true;
+
+        let Some(ref changes) = changes else {
+            continue;
+        };
+
+        let id = output.index as u32;
+        let mut index = 0;
+
+        if let Some(queue) = changes.add_parameter_data(&id, &mut index).upgrade() {
+            let normalized = param.normalize(value) as f64;
+            queue.add_point(0, normalized, &mut index);
+        }
+    }
+
+    changed
+}
+
 fn is_param_flush(data: &mut ProcessData) -> bool {
     data.num_samples == 0 || data.num_outputs == 0 || data.outputs.is_null()
 }
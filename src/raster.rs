@@ -0,0 +1,73 @@
+use ori::prelude::{Canvas, Color};
+use ori_skia::{
+    skia_safe::{self, surfaces, AlphaType, ColorType, ImageInfo, Surface},
+    SkiaFonts,
+};
+
+/// A surface drawn by the CPU, used when no GPU is available.
+pub(crate) struct RasterSurface {
+    surface: Surface,
+    width: u32,
+    height: u32,
+}
+
+impl RasterSurface {
+    /// Create a surface with a size in physical pixels.
+    ///
+    /// The pixels are stored as premultiplied BGRA, matching 24 and 32 bit X11 visuals.
+    pub fn new(width: u32, height: u32) -> Option<Self> {
+        let info = ImageInfo::new(
+            (width.max(1) as i32, height.max(1) as i32),
+            ColorType::BGRA8888,
+            AlphaType::Premul,
+            None,
+        );
+
+        let surface = surfaces::raster(&info, None, None)?;
+
+        Some(Self {
+            surface,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Draw `canvas` to the surface, `scale` is the ratio of physical to logical pixels.
+    pub fn render(&mut self, fonts: &mut SkiaFonts, canvas: &Canvas, color: Color, scale: f32) {
+        let skia = self.surface.canvas();
+
+        let clear = skia_safe::Color4f::new(color.r, color.g, color.b, color.a);
+        skia.clear(clear);
+
+        ori_skia::draw_canvas(skia, fonts, canvas, scale);
+    }
+
+    /// The pixels of the surface, `width * 4` bytes per row.
+    pub fn pixels(&mut self) -> &[u8] {
+        let pixmap = self
+            .surface
+            .peek_pixels()
+            .expect("raster surfaces have pixels");
+
+        // SAFETY: the pixels are owned by the surface, which outlives the slice.
+        unsafe {
+            std::slice::from_raw_parts(pixmap.addr() as *const u8, pixmap.compute_byte_size())
+        }
+    }
+
+    /// Encode the surface as a PNG.
+    pub fn encode_png(&mut self) -> Option<Vec<u8>> {
+        let image = self.surface.image_snapshot();
+        let data = image.encode(None, skia_safe::EncodedImageFormat::PNG, None)?;
+
+        Some(data.as_bytes().to_vec())
+    }
+}
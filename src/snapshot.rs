@@ -0,0 +1,153 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use ori::{
+    app::{AppRequest, UiBuilder},
+    core::command::CommandWaker,
+    prelude::*,
+};
+
+use crate::{raster::RasterSurface, Host, Params, VstPlugin};
+
+/// Renders the user interface of a plugin offscreen, without a host or a display.
+///
+/// ```ignore
+/// let png = Snapshot::new()
+///     .with_size(400, 300)
+///     .with_scale(2.0)
+///     .with_param("gain", 0.5)
+///     .render::<Gain>()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// The logical size, the size of [`VstPlugin::window`] if `None`.
+    pub size: Option<(u32, u32)>,
+
+    /// The ratio of physical to logical pixels.
+    pub scale: f32,
+
+    /// The values of the parameters by identifier, parameters not set keep their defaults.
+    pub params: HashMap<String, f32>,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Snapshot {
+    /// Create a new snapshot.
+    pub fn new() -> Self {
+        Self {
+            size: None,
+            scale: 1.0,
+            params: HashMap::new(),
+        }
+    }
+
+    /// Set the logical size.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Set the ratio of physical to logical pixels.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the value of the parameter `identifier`.
+    pub fn with_param(mut self, identifier: impl Into<String>, value: f32) -> Self {
+        self.params.insert(identifier.into(), value);
+        self
+    }
+
+    /// Render the user interface of `P` to a PNG.
+    pub fn render<P: VstPlugin>(&self) -> io::Result<Vec<u8>> {
+        let mut plugin = P::new();
+        self.set_params(&mut plugin);
+
+        let window = P::window();
+        let id = window.id();
+        let (width, height) = self.size.unwrap_or((window.width(), window.height()));
+
+        let app = App::build().window(window, |plugin: &mut P| any(plugin.ui()));
+
+        let waker = CommandWaker::new(|| {});
+        let fonts = Box::new(P::editor().create_fonts());
+
+        let mut app = app.build(waker, fonts);
+        app.contexts.insert(Host::new());
+
+        app.init(&mut plugin);
+
+        for request in app.take_requests() {
+            if let AppRequest::OpenWindow(window, ui) = request {
+                open_window(&mut app, &mut plugin, window, ui, self.scale);
+            }
+        }
+
+        app.window_resized(&mut plugin, id, width, height);
+
+        let Some(draw) = app.draw_window(&mut plugin, id) else {
+            return Err(io::Error::other("the window was not opened"));
+        };
+
+        let physical_width = (width as f32 * self.scale).round() as u32;
+        let physical_height = (height as f32 * self.scale).round() as u32;
+
+        let Some(mut surface) = RasterSurface::new(physical_width, physical_height) else {
+            return Err(io::Error::other("failed to create surface"));
+        };
+
+        let fonts = app.contexts.get_mut::<Box<dyn Fonts>>().unwrap();
+
+        surface.render(
+            fonts.downcast_mut().unwrap(),
+            &draw.canvas,
+            draw.clear_color,
+            self.scale,
+        );
+
+        surface
+            .encode_png()
+            .ok_or_else(|| io::Error::other("failed to encode png"))
+    }
+
+    /// Render the user interface of `P` to a PNG file at `path`.
+    pub fn save<P: VstPlugin>(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.render::<P>()?)
+    }
+
+    fn set_params<P: VstPlugin>(&self, plugin: &mut P) {
+        let params = plugin.params();
+
+        for i in 0..params.count() {
+            let Some(identifier) = params.identifier(i) else {
+                continue;
+            };
+
+            let Some(value) = self.params.get(&identifier) else {
+                continue;
+            };
+
+            if let Some(param) = params.param(i) {
+                param.set(*value);
+            }
+        }
+    }
+}
+
+fn open_window<P: VstPlugin>(
+    app: &mut App<P>,
+    plugin: &mut P,
+    window: Window,
+    ui: UiBuilder<P>,
+    scale: f32,
+) {
+    let id = window.id();
+
+    app.add_window(plugin, ui, window);
+    app.window_scaled(plugin, id, scale);
+}
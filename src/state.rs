@@ -7,17 +7,32 @@ use parking_lot::Mutex;
 
 use crate::{
     controller::ComponentHandler, editor::EditorHandle, param_values, AudioLayout, BufferLayout,
-    Buffers, Process, VstPlugin,
+    Buffers, Bypass, Host, HostApplication, ParamFlags, Params, Process, VstPlugin,
 };
 
+/// A read-only parameter written by the plugin, and the last value reported to the host.
+pub(crate) struct OutputParam {
+    pub index: usize,
+    pub value: f32,
+}
+
 pub(crate) struct PluginState<P: VstPlugin> {
     pub plugin: Mutex<P>,
     pub audio_layout: Mutex<Arc<AudioLayout>>,
     pub buffer_layout: Mutex<Option<BufferLayout>>,
     pub buffers: Mutex<Buffers>,
+    pub bypass: Mutex<Bypass>,
+    pub output_params: Mutex<Vec<OutputParam>>,
+    pub changed_params: Mutex<Vec<usize>>,
+
+    /// The parameters set by the host since the editor last looked, including the bypass.
+    ///
+    /// Written while holding the plugin lock, so the editor can tell its own edits apart.
+    pub host_set: Box<[AtomicBool]>,
     pub status: Mutex<Process>,
     pub editor: Mutex<Option<Arc<dyn EditorHandle>>>,
     pub component: Mutex<Option<ComponentHandler>>,
+    pub host: Host,
     pub latency: AtomicU32,
     pub processing: AtomicBool,
 }
@@ -30,17 +45,25 @@ impl<P: VstPlugin> Default for PluginState<P> {
 
 impl<P: VstPlugin> PluginState<P> {
     pub fn new() -> Self {
-        let plugin = P::new();
+        let mut plugin = P::new();
         let layout = P::default_layout();
 
+        let count = plugin.params().count() + 1;
+        let host_set = (0..count).map(|_| AtomicBool::new(false)).collect();
+
         Self {
             plugin: Mutex::new(plugin),
             audio_layout: Mutex::new(Arc::new(layout)),
             buffer_layout: Mutex::new(None),
             buffers: Mutex::new(Buffers::new()),
+            bypass: Mutex::new(Bypass::new()),
+            output_params: Mutex::new(Vec::new()),
+            changed_params: Mutex::new(Vec::new()),
+            host_set,
             status: Mutex::new(Process::Done),
             editor: Mutex::new(None),
             component: Mutex::new(None),
+            host: Host::new(),
             latency: AtomicU32::new(0),
             processing: AtomicBool::new(false),
         }
@@ -62,6 +85,10 @@ impl<P: VstPlugin> PluginState<P> {
         *self.buffer_layout.lock() = layout;
     }
 
+    pub fn set_host(&self, host: Option<HostApplication>) {
+        self.host.set_application(host);
+    }
+
     pub fn status(&self) -> Process {
         *self.status.lock()
     }
@@ -92,6 +119,115 @@ impl<P: VstPlugin> PluginState<P> {
         buffers.allocate(layout);
     }
 
+    /// Activate `plugin` with the current layouts, returns false if there is no buffer layout.
+    pub fn activate(&self, plugin: &mut P) -> bool {
+        let Some(buffer_layout) = self.buffer_layout() else {
+            return false;
+        };
+
+        let audio_layout = self.audio_layout();
+        self.allocate_buffers(&audio_layout);
+
+        let config = plugin.activate(&self.host, &audio_layout, &buffer_layout);
+        self.set_latency(config.latency);
+        self.update_output_params(plugin.params());
+
+        let mut changed_params = self.changed_params.lock();
+        changed_params.clear();
+        changed_params.reserve(plugin.params().count());
+        drop(changed_params);
+
+        let channels = audio_layout.output.as_ref().map_or(0, |port| port.channels);
+        let mut bypass = self.bypass.lock();
+        bypass.activate(channels as usize, config.latency, &buffer_layout);
+
+        true
+    }
+
+    /// Find the read-only parameters of the plugin.
+    ///
+    /// This allocates, so it's done when the plugin is activated and not while processing.
+    pub fn update_output_params(&self, params: &mut dyn Params) {
+        let mut output_params = self.output_params.lock();
+        output_params.clear();
+
+        for index in 0..params.count() {
+            let Some(info) = params.info(index) else {
+                continue;
+            };
+
+            if !info.flags.contains(ParamFlags::READ_ONLY) {
+                continue;
+            }
+
+            if let Some(param) = params.param(index) {
+                let value = param.get();
+                output_params.push(OutputParam { index, value });
+            }
+        }
+    }
+
+    /// Call [`VstPlugin::param_changed`] for the parameters changed since the last call.
+    ///
+    /// This doesn't allocate once the plugin is activated, so it's safe to call while processing.
+    pub fn notify_changed_params(&self, plugin: &mut P) {
+        let mut changed_params = self.changed_params.lock();
+        changed_params.clear();
+
+        plugin
+            .params()
+            .take_changed(&mut |index| changed_params.push(index));
+
+        for &index in changed_params.iter() {
+            plugin.param_changed(index);
+        }
+    }
+
+    /// Note that the host set the parameter at `index`, the plugin lock must be held.
+    pub fn set_by_host(&self, index: usize) {
+        if let Some(host_set) = self.host_set.get(index) {
+            host_set.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Find the parameters edited by the editor since `values` were read, returning the current
+    /// values.
+    ///
+    /// The editor writes the values directly, so the edited parameters are marked as changed.
+    /// Parameters set by the host, and read-only parameters, aren't edits.
+    pub fn take_editor_edits(&self, values: &[f32], edits: &mut Vec<usize>) -> Vec<f32> {
+        let mut plugin = self.plugin.lock();
+        let output_params = self.output_params.lock();
+
+        let params = plugin.params();
+        let new_values = param_values(params);
+
+        for (index, (old, new)) in values.iter().zip(&new_values).enumerate() {
+            let host_set = self.host_set[index].swap(false, Ordering::Relaxed);
+            let output = output_params.iter().any(|param| param.index == index);
+
+            if old == new || host_set || output {
+                continue;
+            }
+
+            if let Some(param) = params.param(index) {
+                param.set(param.get());
+            }
+
+            edits.push(index);
+        }
+
+        new_values
+    }
+
+    /// Call `f` with the parameters of the plugin, including the bypass parameter.
+    pub fn with_params<T>(&self, f: impl FnOnce(&mut dyn Params) -> T) -> T {
+        let mut plugin = self.plugin.lock();
+        let mut bypass = self.bypass.lock();
+
+        f(&mut bypass.with_params(plugin.params()))
+    }
+
     pub fn param_values(&self) -> Vec<f32> {
         let mut plugin = self.plugin.lock();
         param_values(plugin.params())
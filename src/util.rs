@@ -1,26 +1,48 @@
-use std::{ffi::c_char, mem, ptr};
+use std::{ffi::c_char, ptr};
 
+/// Copy `src` into `dst` as a NUL-terminated UTF-8 string.
+///
+/// Strings that don't fit are truncated at a character boundary.
 pub unsafe fn strcpy(src: &str, dst: &mut [c_char]) {
-    if src.len() >= dst.len() {
+    if dst.is_empty() {
         return;
     }
 
-    ptr::copy_nonoverlapping(src.as_ptr() as *const c_char, dst.as_mut_ptr(), src.len());
+    let mut len = usize::min(src.len(), dst.len() - 1);
+
+    while !src.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    ptr::copy_nonoverlapping(src.as_ptr() as *const c_char, dst.as_mut_ptr(), len);
+    dst[len] = 0;
 }
 
+/// Copy `src` into `dst` as a NUL-terminated UTF-16 string.
+///
+/// Strings that don't fit are truncated at a character boundary.
 pub unsafe fn u16strcpy(src: &str, dst: &mut [i16]) {
     if dst.is_empty() {
         return;
     }
 
-    for (i, c) in src.encode_utf16().enumerate() {
-        if i >= dst.len() {
+    let mut len = 0;
+
+    for c in src.chars() {
+        let mut buf = [0; 2];
+        let units = c.encode_utf16(&mut buf);
+
+        if len + units.len() >= dst.len() {
             break;
         }
 
-        // SAFETY: `i16` has no invalid bit patterns.
-        dst[i] = mem::transmute::<u16, i16>(c);
+        for unit in units {
+            dst[len] = *unit as i16;
+            len += 1;
+        }
     }
+
+    dst[len] = 0;
 }
 
 pub unsafe fn u16strlen(src: *const i16) -> usize {
@@ -32,3 +54,71 @@ pub unsafe fn u16strlen(src: *const i16) -> usize {
 
     len
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{c_char, CStr};
+
+    use super::*;
+
+    fn copy(src: &str, len: usize) -> String {
+        let mut dst = vec![1 as c_char; len];
+        unsafe { strcpy(src, &mut dst) };
+
+        let str = unsafe { CStr::from_ptr(dst.as_ptr()) };
+        str.to_str().unwrap().to_owned()
+    }
+
+    fn copy_u16(src: &str, len: usize) -> String {
+        let mut dst = vec![1; len];
+        unsafe { u16strcpy(src, &mut dst) };
+
+        let len = unsafe { u16strlen(dst.as_ptr()) };
+        let units: Vec<u16> = dst[..len].iter().map(|&unit| unit as u16).collect();
+        String::from_utf16(&units).unwrap()
+    }
+
+    #[test]
+    fn strcpy_fits() {
+        assert_eq!(copy("gain", 5), "gain");
+        assert_eq!(copy("", 1), "");
+    }
+
+    #[test]
+    fn strcpy_truncates_at_char_boundary() {
+        assert_eq!(copy("gain", 3), "ga");
+
+        // "é" is two bytes, there is room for "ab" and one byte of it
+        assert_eq!(copy("abé", 4), "ab");
+        assert_eq!(copy("abé", 5), "abé");
+
+        // "€" is three bytes
+        assert_eq!(copy("€€", 6), "€");
+    }
+
+    #[test]
+    fn strcpy_empty_dst() {
+        unsafe { strcpy("gain", &mut []) };
+    }
+
+    #[test]
+    fn u16strcpy_fits() {
+        assert_eq!(copy_u16("gain", 5), "gain");
+        assert_eq!(copy_u16("é€", 3), "é€");
+    }
+
+    #[test]
+    fn u16strcpy_truncates_at_surrogate_pair() {
+        assert_eq!(copy_u16("gain", 3), "ga");
+
+        // "🎵" is a surrogate pair, it's never split
+        assert_eq!(copy_u16("a🎵", 3), "a");
+        assert_eq!(copy_u16("a🎵", 4), "a🎵");
+        assert_eq!(copy_u16("🎵🎵", 4), "🎵");
+    }
+
+    #[test]
+    fn u16strcpy_empty_dst() {
+        unsafe { u16strcpy("gain", &mut []) };
+    }
+}
@@ -8,22 +8,68 @@ use parking_lot::Mutex;
 use vst3_com::VstPtr;
 use vst3_sys::{
     base::{char16, kResultFalse, kResultOk, kResultTrue, tresult, FIDString, TBool},
-    gui::{IPlugFrame, IPlugView, ViewRect},
+    gui::{IPlugFrame, IPlugView, IPlugViewContentScaleSupport, ViewRect},
     utils::SharedVstPtr,
     VST3,
 };
 
 use crate::{PluginState, VstPlugin};
 
-#[VST3(implements(IPlugView))]
+/// The `IPlugFrame` of a view, used to resize the view from the editor.
+#[derive(Clone)]
+pub(crate) struct PlugFrame {
+    frame: VstPtr<dyn IPlugFrame>,
+
+    /// The view, only referenced while attached, so the frame doesn't keep the view alive.
+    view: Option<VstPtr<dyn IPlugView>>,
+}
+
+unsafe impl Send for PlugFrame {}
+
+impl PlugFrame {
+    /// Ask the host to resize the view to a physical size.
+    ///
+    /// The host calls `IPlugView::on_size` if it accepts the size, this must be called from the
+    /// host's UI thread.
+    pub unsafe fn resize_view(&self, width: u32, height: u32) -> bool {
+        let Some(ref view) = self.view else {
+            return false;
+        };
+
+        let mut rect = ViewRect {
+            left: 0,
+            top: 0,
+            right: width as i32,
+            bottom: height as i32,
+        };
+
+        let view: SharedVstPtr<dyn IPlugView> = mem::transmute(view.as_raw());
+        self.frame.resize_view(view, &mut rect) == kResultOk
+    }
+
+    /// Get the run loop of the host, provided by the frame on Linux.
+    #[cfg(target_os = "linux")]
+    pub fn run_loop(&self) -> Option<VstPtr<dyn crate::x11::IRunLoop>> {
+        self.frame.cast::<dyn crate::x11::IRunLoop>()
+    }
+}
+
+#[VST3(implements(IPlugView, IPlugViewContentScaleSupport))]
 pub struct RawView<P: VstPlugin> {
     state: Arc<PluginState<P>>,
-    frame: Mutex<Option<VstPtr<dyn IPlugFrame>>>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+    scale: Mutex<Option<f32>>,
 }
 
 impl<P: VstPlugin> RawView<P> {
     pub fn new(state: Arc<PluginState<P>>) -> Box<Self> {
-        Self::allocate(state, Mutex::new(None))
+        Self::allocate(state, Arc::new(Mutex::new(None)), Mutex::new(None))
+    }
+
+    /// Take a reference to the view, the view is the first interface of `Self`.
+    unsafe fn counted(&self) -> Option<VstPtr<dyn IPlugView>> {
+        let view: SharedVstPtr<dyn IPlugView> = mem::transmute(self as *const Self);
+        view.upgrade()
     }
 }
 
@@ -45,31 +91,46 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
     }
 
     unsafe fn attached(&self, parent: *mut c_void, type_: FIDString) -> tresult {
-        let mut editor = self.state.editor.lock();
-
-        if editor.is_some() {
+        if self.state.editor.lock().is_some() {
             return kResultFalse;
         }
 
         let c_str = CStr::from_ptr(type_);
 
+        if let Some(ref mut frame) = *self.frame.lock() {
+            frame.view = self.counted();
+        }
+
+        // the editor isn't locked while spawning, as the host may call back into the view
         let new_handle = match c_str.to_str() {
             #[cfg(target_os = "linux")]
             Ok(t) if t == "X11EmbedWindowID" => {
-                crate::x11::spawn_editor(self.state.clone(), parent)
+                let scale = *self.scale.lock();
+                crate::x11::spawn_editor(self.state.clone(), parent, scale, self.frame.clone())
+            }
+            _ => {
+                if let Some(ref mut frame) = *self.frame.lock() {
+                    frame.view.take();
+                }
+
+                return kResultFalse;
             }
-            _ => return kResultFalse,
         };
 
-        *editor = Some(new_handle);
+        *self.state.editor.lock() = Some(new_handle);
 
         kResultOk
     }
 
     unsafe fn removed(&self) -> tresult {
-        let mut editor = self.state.editor.lock();
+        // release the view, otherwise the frame would keep it alive
+        if let Some(ref mut frame) = *self.frame.lock() {
+            frame.view.take();
+        }
+
+        let editor = self.state.editor.lock().take();
 
-        if let Some(handle) = editor.take() {
+        if let Some(handle) = editor {
             handle.quit();
             return kResultOk;
         }
@@ -77,22 +138,45 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
         kResultFalse
     }
 
-    unsafe fn on_wheel(&self, _distance: f32) -> tresult {
-        kResultOk
+    unsafe fn on_wheel(&self, distance: f32) -> tresult {
+        // don't hold the lock while the editor handles the wheel
+        let Some(editor) = self.state.editor.lock().clone() else {
+            return kResultFalse;
+        };
+
+        match editor.wheel(distance) {
+            true => kResultTrue,
+            false => kResultFalse,
+        }
     }
 
-    unsafe fn on_key_down(&self, _key: char16, _key_code: i16, _modifiers: i16) -> tresult {
-        kResultOk
+    unsafe fn on_key_down(&self, key: char16, key_code: i16, modifiers: i16) -> tresult {
+        let Some(editor) = self.state.editor.lock().clone() else {
+            return kResultFalse;
+        };
+
+        // only report keys as handled when consumed, so host shortcuts keep working
+        match editor.key(key as u16, key_code, modifiers, true) {
+            true => kResultTrue,
+            false => kResultFalse,
+        }
     }
 
-    unsafe fn on_key_up(&self, _key: char16, _key_code: i16, _modifiers: i16) -> tresult {
-        kResultOk
+    unsafe fn on_key_up(&self, key: char16, key_code: i16, modifiers: i16) -> tresult {
+        let Some(editor) = self.state.editor.lock().clone() else {
+            return kResultFalse;
+        };
+
+        match editor.key(key as u16, key_code, modifiers, false) {
+            true => kResultTrue,
+            false => kResultFalse,
+        }
     }
 
     unsafe fn get_size(&self, size: *mut ViewRect) -> tresult {
-        let editor = self.state.editor.lock();
+        let editor = self.state.editor.lock().clone();
 
-        if let Some(editor) = editor.as_ref() {
+        if let Some(editor) = editor {
             let size = &mut *size;
 
             let (width, height) = editor.size();
@@ -107,9 +191,10 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
     }
 
     unsafe fn on_size(&self, new_size: *mut ViewRect) -> tresult {
-        let editor = self.state.editor.lock();
+        // don't hold the lock, the host may call `on_size` again from inside the resize
+        let editor = self.state.editor.lock().clone();
 
-        if let Some(editor) = editor.as_ref() {
+        if let Some(editor) = editor {
             let new_size = &*new_size;
 
             let width = new_size.right - new_size.left;
@@ -122,6 +207,7 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
                 return kResultFalse;
             }
 
+            let (width, height) = editor.constrain(width, height);
             editor.resize(width, height);
         }
 
@@ -136,7 +222,12 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
         let frame: SharedVstPtr<dyn IPlugFrame> = mem::transmute(frame);
         match frame.upgrade() {
             Some(frame) => {
-                self.frame.lock().replace(frame);
+                let view = match self.state.editor.lock().is_some() {
+                    true => self.counted(),
+                    false => None,
+                };
+
+                self.frame.lock().replace(PlugFrame { frame, view });
             }
             None => {
                 self.frame.lock().take();
@@ -147,9 +238,9 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
     }
 
     unsafe fn can_resize(&self) -> tresult {
-        let editor = self.state.editor.lock();
+        let editor = self.state.editor.lock().clone();
 
-        if let Some(handle) = editor.as_ref() {
+        if let Some(handle) = editor {
             if handle.resizable() {
                 return kResultTrue;
             }
@@ -159,22 +250,210 @@ impl<P: VstPlugin> IPlugView for RawView<P> {
     }
 
     unsafe fn check_size_constraint(&self, rect: *mut ViewRect) -> tresult {
-        let editor = self.state.editor.lock();
+        let editor = self.state.editor.lock().clone();
 
-        if let Some(editor) = editor.as_ref() {
-            let rect = &*rect;
+        if let Some(editor) = editor {
+            let rect = &mut *rect;
 
             let width = rect.right - rect.left;
             let height = rect.bottom - rect.top;
 
-            let width = width as u32;
-            let height = height as u32;
+            let (width, height) = editor.constrain(width.max(0) as u32, height.max(0) as u32);
 
-            if !editor.resizable() && (width, height) != editor.size() {
+            rect.right = rect.left + width as i32;
+            rect.bottom = rect.top + height as i32;
+        }
+
+        kResultTrue
+    }
+}
+
+impl<P: VstPlugin> IPlugViewContentScaleSupport for RawView<P> {
+    unsafe fn set_content_scale_factor(&self, factor: f32) -> tresult {
+        if factor <= 0.0 {
+            return kResultFalse;
+        }
+
+        *self.scale.lock() = Some(factor);
+
+        // don't hold the lock, the editor may resize through the host, which calls `on_size`
+        let editor = self.state.editor.lock().clone();
+
+        if let Some(editor) = editor {
+            editor.set_scale(factor);
+        }
+
+        kResultOk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ptr::NonNull,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use ori::prelude::*;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        editor::EditorHandle, AudioLayout, Buffer, BufferLayout, Info, Process, VstPlugin,
+    };
+
+    struct TestPlugin;
+
+    impl VstPlugin for TestPlugin {
+        fn info() -> Info {
+            Info {
+                uuid: Uuid::nil(),
+                name: String::from("Test"),
+                subcategories: Vec::new(),
+                vendor: String::new(),
+                version: String::new(),
+                url: String::new(),
+                email: String::new(),
+            }
+        }
+
+        fn layout(_inputs: &[u32], _outputs: &[u32]) -> Option<AudioLayout> {
+            None
+        }
+
+        fn new() -> Self {
+            Self
+        }
+
+        fn ui(&mut self) -> impl View<Self> + 'static {
+            text("Test")
+        }
+
+        fn process(
+            &mut self,
+            _buffer: &mut Buffer<'_>,
+            _aux_buffers: &mut [Buffer<'_>],
+            _layout: BufferLayout,
+        ) -> Process {
+            Process::Done
+        }
+    }
+
+    /// A frame accepting every size, calling `on_size` from inside `resize_view` like hosts do.
+    #[VST3(implements(IPlugFrame))]
+    struct TestFrame {
+        resizes: AtomicU32,
+    }
+
+    impl IPlugFrame for TestFrame {
+        unsafe fn resize_view(
+            &self,
+            view: SharedVstPtr<dyn IPlugView>,
+            new_size: *mut ViewRect,
+        ) -> tresult {
+            let Some(view) = view.upgrade() else {
                 return kResultFalse;
+            };
+
+            self.resizes.fetch_add(1, Ordering::Relaxed);
+            view.on_size(new_size)
+        }
+    }
+
+    /// An editor resizing through the host, like the editor on the host's run loop.
+    struct TestEditor {
+        frame: Arc<Mutex<Option<PlugFrame>>>,
+        size: Mutex<(u32, u32)>,
+    }
+
+    impl EditorHandle for TestEditor {
+        fn quit(&self) {}
+
+        fn size(&self) -> (u32, u32) {
+            *self.size.lock()
+        }
+
+        fn resize(&self, width: u32, height: u32) {
+            let old_size = mem::replace(&mut *self.size.lock(), (width, height));
+
+            if old_size == (width, height) {
+                return;
+            }
+
+            let frame = self.frame.lock().clone();
+
+            if let Some(frame) = frame {
+                unsafe { frame.resize_view(width, height) };
             }
         }
 
-        kResultTrue
+        fn resizable(&self) -> bool {
+            true
+        }
+
+        fn rebuild(&self) {}
+
+        fn scale(&self) -> f32 {
+            1.0
+        }
+
+        fn set_scale(&self, scale: f32) {
+            let (width, height) = self.size();
+            self.resize(width * scale as u32, height * scale as u32);
+        }
+
+        fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+            (width, height)
+        }
+
+        fn key(&self, _key: u16, _key_code: i16, _modifiers: i16, _pressed: bool) -> bool {
+            false
+        }
+
+        fn wheel(&self, _distance: f32) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn host_calls_on_size_from_resize_view() {
+        unsafe {
+            let state = Arc::new(PluginState::<TestPlugin>::new());
+
+            let raw = Box::into_raw(RawView::new(state.clone()));
+            let _view = VstPtr::<dyn IPlugView>::owned(NonNull::new(raw).unwrap().cast());
+
+            let test_frame = Box::into_raw(TestFrame::allocate(AtomicU32::new(0)));
+            let frame = NonNull::new(test_frame).unwrap().cast();
+            let frame = VstPtr::<dyn IPlugFrame>::owned(frame);
+            assert_eq!((*raw).set_frame(frame.as_raw() as *mut c_void), kResultOk);
+
+            // what `attached` does, without spawning an editor
+            if let Some(ref mut frame) = *(*raw).frame.lock() {
+                frame.view = (*raw).counted();
+            }
+
+            let editor = TestEditor {
+                frame: (*raw).frame.clone(),
+                size: Mutex::new((100, 100)),
+            };
+            *state.editor.lock() = Some(Arc::new(editor));
+
+            let mut rect = ViewRect {
+                left: 0,
+                top: 0,
+                right: 200,
+                bottom: 150,
+            };
+
+            assert_eq!((*raw).on_size(&mut rect), kResultOk);
+            assert_eq!(state.editor.lock().as_ref().unwrap().size(), (200, 150));
+
+            assert_eq!((*raw).set_content_scale_factor(2.0), kResultOk);
+            assert_eq!(state.editor.lock().as_ref().unwrap().size(), (400, 300));
+            assert_eq!((*test_frame).resizes.load(Ordering::Relaxed), 2);
+
+            assert_eq!((*raw).removed(), kResultOk);
+        }
     }
 }
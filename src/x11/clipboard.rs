@@ -0,0 +1,277 @@
+use std::{
+    ffi::{c_int, c_long, c_uchar, c_ulong, CStr},
+    mem, ptr, slice,
+    sync::Arc,
+};
+
+use ori::core::clipboard::ClipboardBackend;
+use parking_lot::Mutex;
+use x11_dl::xlib::{
+    self, AnyPropertyType, CurrentTime, Display, PropModeReplace, SelectionNotify, XEvent,
+    XSelectionEvent, XSelectionRequestEvent,
+};
+
+use super::XLIB;
+
+/// The `CLIPBOARD` and `PRIMARY` selections of the editor window.
+///
+/// Converting a selection is asynchronous on X11, so the contents of the clipboard are requested
+/// ahead of time, see [`Selections::request`], and cached when the owner replies.
+pub struct Selections {
+    display: *mut Display,
+    window: xlib::Window,
+    atoms: Atoms,
+    clipboard: Option<String>,
+    primary: Option<String>,
+    cache: String,
+}
+
+unsafe impl Send for Selections {}
+
+struct Atoms {
+    clipboard: xlib::Atom,
+    primary: xlib::Atom,
+    targets: xlib::Atom,
+    utf8_string: xlib::Atom,
+    string: xlib::Atom,
+    text: xlib::Atom,
+    property: xlib::Atom,
+}
+
+impl Selections {
+    pub unsafe fn new(display: *mut Display) -> Self {
+        let atom = |name: &CStr| (XLIB.XInternAtom)(display, name.as_ptr(), 0);
+
+        let atoms = Atoms {
+            clipboard: atom(c"CLIPBOARD"),
+            primary: xlib::XA_PRIMARY,
+            targets: atom(c"TARGETS"),
+            utf8_string: atom(c"UTF8_STRING"),
+            string: xlib::XA_STRING,
+            text: atom(c"TEXT"),
+            property: atom(c"ORI_VST_SELECTION"),
+        };
+
+        Self {
+            display,
+            window: 0,
+            atoms,
+            clipboard: None,
+            primary: None,
+            cache: String::new(),
+        }
+    }
+
+    /// Set the window that owns the selections.
+    pub fn set_window(&mut self, window: xlib::Window) {
+        self.window = window;
+    }
+
+    /// Request the contents of `CLIPBOARD`, or `PRIMARY` if nobody owns it.
+    ///
+    /// The reply arrives as a `SelectionNotify` event.
+    pub unsafe fn request(&mut self) {
+        if self.window == 0 || self.clipboard.is_some() {
+            return;
+        }
+
+        let selection = match (XLIB.XGetSelectionOwner)(self.display, self.atoms.clipboard) {
+            0 => self.atoms.primary,
+            _ => self.atoms.clipboard,
+        };
+
+        (XLIB.XConvertSelection)(
+            self.display,
+            selection,
+            self.atoms.utf8_string,
+            self.atoms.property,
+            self.window,
+            CurrentTime,
+        );
+    }
+
+    fn get(&self) -> String {
+        match self.clipboard {
+            Some(ref text) => text.clone(),
+            None => self.cache.clone(),
+        }
+    }
+
+    unsafe fn set(&mut self, text: &str) {
+        self.clipboard = Some(String::from(text));
+        self.primary = Some(String::from(text));
+
+        if self.window == 0 {
+            return;
+        }
+
+        (XLIB.XSetSelectionOwner)(self.display, self.atoms.clipboard, self.window, CurrentTime);
+        (XLIB.XSetSelectionOwner)(self.display, self.atoms.primary, self.window, CurrentTime);
+        (XLIB.XFlush)(self.display);
+    }
+
+    /// Handle selection events, returns true if `event` was a selection event.
+    pub unsafe fn handle_event(&mut self, event: &XEvent) -> bool {
+        match event.get_type() {
+            xlib::SelectionRequest => self.handle_request(&event.selection_request),
+            xlib::SelectionNotify => self.handle_notify(&event.selection),
+            xlib::SelectionClear => {
+                let selection = event.selection_clear.selection;
+
+                if selection == self.atoms.clipboard {
+                    self.clipboard = None;
+                } else if selection == self.atoms.primary {
+                    self.primary = None;
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    unsafe fn handle_request(&mut self, request: &XSelectionRequestEvent) {
+        let text = match request.selection {
+            selection if selection == self.atoms.clipboard => self.clipboard.as_ref(),
+            selection if selection == self.atoms.primary => self.primary.as_ref(),
+            _ => None,
+        };
+
+        // obsolete clients pass no property, and expect the target to be used
+        let property = match request.property {
+            0 => request.target,
+            property => property,
+        };
+
+        let atoms = &self.atoms;
+
+        let converted = match text {
+            Some(_) if request.target == atoms.targets => {
+                let targets = [atoms.targets, atoms.utf8_string, atoms.string, atoms.text];
+
+                (XLIB.XChangeProperty)(
+                    self.display,
+                    request.requestor,
+                    property,
+                    xlib::XA_ATOM,
+                    32,
+                    PropModeReplace,
+                    targets.as_ptr() as *const c_uchar,
+                    targets.len() as c_int,
+                );
+
+                true
+            }
+            Some(text)
+                if request.target == atoms.utf8_string
+                    || request.target == atoms.string
+                    || request.target == atoms.text =>
+            {
+                // STRING is Latin-1, characters outside of it are replaced
+                let (kind, bytes) = match request.target == atoms.string {
+                    true => (atoms.string, encode_latin1(text)),
+                    false => (atoms.utf8_string, text.as_bytes().to_vec()),
+                };
+
+                (XLIB.XChangeProperty)(
+                    self.display,
+                    request.requestor,
+                    property,
+                    kind,
+                    8,
+                    PropModeReplace,
+                    bytes.as_ptr(),
+                    bytes.len() as c_int,
+                );
+
+                true
+            }
+            _ => false,
+        };
+
+        let mut notify: XEvent = mem::zeroed();
+        notify.selection = XSelectionEvent {
+            type_: SelectionNotify,
+            serial: 0,
+            send_event: 1,
+            display: self.display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if converted { property } else { 0 },
+            time: request.time,
+        };
+
+        (XLIB.XSendEvent)(self.display, request.requestor, 0, 0, &mut notify);
+        (XLIB.XFlush)(self.display);
+    }
+
+    unsafe fn handle_notify(&mut self, notify: &XSelectionEvent) {
+        if notify.property == 0 {
+            return;
+        }
+
+        let mut kind = 0;
+        let mut format = 0;
+        let mut items = 0;
+        let mut remaining = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+
+        (XLIB.XGetWindowProperty)(
+            self.display,
+            self.window,
+            notify.property,
+            0,
+            c_long::MAX / 4,
+            1,
+            AnyPropertyType as c_ulong,
+            &mut kind,
+            &mut format,
+            &mut items,
+            &mut remaining,
+            &mut data,
+        );
+
+        if data.is_null() {
+            return;
+        }
+
+        // large transfers use the INCR protocol, which isn't supported
+        if format == 8 && kind == self.atoms.utf8_string {
+            let bytes = slice::from_raw_parts(data, items as usize);
+            self.cache = String::from_utf8_lossy(bytes).into_owned();
+        } else if format == 8 && kind == self.atoms.string {
+            let bytes = slice::from_raw_parts(data, items as usize);
+            self.cache = bytes.iter().map(|&byte| byte as char).collect();
+        }
+
+        (XLIB.XFree)(data as *mut _);
+    }
+}
+
+/// Encode `text` as Latin-1, replacing characters outside of it with `?`.
+fn encode_latin1(text: &str) -> Vec<u8> {
+    let byte = |c: char| u8::try_from(c).unwrap_or(b'?');
+    text.chars().map(byte).collect()
+}
+
+/// The clipboard backend inserted into the `ori` contexts.
+pub struct X11Clipboard {
+    selections: Arc<Mutex<Selections>>,
+}
+
+impl X11Clipboard {
+    pub fn new(selections: Arc<Mutex<Selections>>) -> Self {
+        Self { selections }
+    }
+}
+
+impl ClipboardBackend for X11Clipboard {
+    fn get(&mut self) -> String {
+        self.selections.lock().get()
+    }
+
+    fn set(&mut self, text: &str) {
+        unsafe { self.selections.lock().set(text) };
+    }
+}
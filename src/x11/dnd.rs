@@ -0,0 +1,478 @@
+use std::{
+    ffi::{c_long, c_uchar, c_ulong, CStr, OsString},
+    mem,
+    os::unix::ffi::OsStringExt,
+    path::PathBuf,
+    ptr, slice,
+};
+
+use ori::prelude::Point;
+use x11_dl::xlib::{
+    self, AnyPropertyType, ClientMessage, Display, PropModeReplace, XClientMessageEvent, XEvent,
+    XSelectionEvent,
+};
+
+use super::XLIB;
+use crate::FileDrop;
+
+/// The version of the XDND protocol supported.
+const XDND_VERSION: c_long = 5;
+
+struct Atoms {
+    aware: xlib::Atom,
+    enter: xlib::Atom,
+    position: xlib::Atom,
+    status: xlib::Atom,
+    leave: xlib::Atom,
+    drop: xlib::Atom,
+    finished: xlib::Atom,
+    selection: xlib::Atom,
+    type_list: xlib::Atom,
+    action_copy: xlib::Atom,
+    uri_list: xlib::Atom,
+    property: xlib::Atom,
+}
+
+/// The state of an XDND drag over the editor.
+///
+/// The dragged paths are requested when the drag enters, so they can be reported while
+/// hovering, and again on drop if they weren't received yet.
+pub struct Dnd {
+    atoms: Atoms,
+    source: xlib::Window,
+    target: xlib::Window,
+    accepted: bool,
+    position: Point,
+    paths: Option<Vec<PathBuf>>,
+    dropped: Option<xlib::Time>,
+}
+
+impl Dnd {
+    pub unsafe fn new(display: *mut Display) -> Self {
+        let atom = |name: &CStr| (XLIB.XInternAtom)(display, name.as_ptr(), 0);
+
+        let atoms = Atoms {
+            aware: atom(c"XdndAware"),
+            enter: atom(c"XdndEnter"),
+            position: atom(c"XdndPosition"),
+            status: atom(c"XdndStatus"),
+            leave: atom(c"XdndLeave"),
+            drop: atom(c"XdndDrop"),
+            finished: atom(c"XdndFinished"),
+            selection: atom(c"XdndSelection"),
+            type_list: atom(c"XdndTypeList"),
+            action_copy: atom(c"XdndActionCopy"),
+            uri_list: atom(c"text/uri-list"),
+            property: atom(c"ORI_VST_DND"),
+        };
+
+        Self {
+            atoms,
+            source: 0,
+            target: 0,
+            accepted: false,
+            position: Point::ZERO,
+            paths: None,
+            dropped: None,
+        }
+    }
+
+    /// Mark `window` as accepting drops.
+    pub unsafe fn set_aware(&self, display: *mut Display, window: xlib::Window) {
+        let version = XDND_VERSION as xlib::Atom;
+
+        (XLIB.XChangeProperty)(
+            display,
+            window,
+            self.atoms.aware,
+            xlib::XA_ATOM,
+            32,
+            PropModeReplace,
+            &version as *const _ as *const c_uchar,
+            1,
+        );
+    }
+
+    /// Whether `event` is the reply to a request for the dragged paths.
+    pub fn is_selection(&self, event: &XEvent) -> bool {
+        event.get_type() == xlib::SelectionNotify
+            && unsafe { event.selection.selection } == self.atoms.selection
+    }
+
+    /// Handle an XDND client message, `scale` converts the position to logical pixels.
+    pub unsafe fn handle_client_message(
+        &mut self,
+        display: *mut Display,
+        window: xlib::Window,
+        event: &XClientMessageEvent,
+        scale: f32,
+    ) -> Option<FileDrop> {
+        let data = &event.data;
+        let source = data.get_long(0) as xlib::Window;
+
+        if event.message_type == self.atoms.enter {
+            self.source = source;
+            self.target = window;
+            self.paths = None;
+            self.dropped = None;
+            self.accepted = self.offers_uri_list(display, event);
+
+            if self.accepted {
+                self.request_paths(display, xlib::CurrentTime);
+            }
+
+            return None;
+        }
+
+        if source != self.source {
+            return None;
+        }
+
+        if event.message_type == self.atoms.position {
+            let root = data.get_long(2);
+            let (x, y) = ((root >> 16) & 0xffff, root & 0xffff);
+
+            self.position = local_position(display, window, x as i32, y as i32, scale);
+            self.send_status(display);
+
+            let paths = self.paths.clone()?;
+
+            return Some(FileDrop::Hovered {
+                position: self.position,
+                paths,
+            });
+        }
+
+        if event.message_type == self.atoms.leave {
+            self.source = 0;
+            self.paths = None;
+
+            return Some(FileDrop::Left);
+        }
+
+        if event.message_type == self.atoms.drop {
+            let time = data.get_long(2) as xlib::Time;
+
+            if !self.accepted {
+                self.send_finished(display, false);
+                return Some(FileDrop::Left);
+            }
+
+            return match self.paths.take() {
+                Some(paths) => {
+                    self.send_finished(display, true);
+
+                    Some(FileDrop::Dropped {
+                        position: self.position,
+                        paths,
+                    })
+                }
+                None => {
+                    // the paths haven't arrived yet, finish when they do
+                    self.dropped = Some(time);
+                    self.request_paths(display, time);
+
+                    None
+                }
+            };
+        }
+
+        None
+    }
+
+    /// Handle the dragged paths arriving.
+    pub unsafe fn handle_selection(
+        &mut self,
+        display: *mut Display,
+        event: &XSelectionEvent,
+    ) -> Option<FileDrop> {
+        if event.property == 0 || self.source == 0 {
+            return None;
+        }
+
+        let paths = read_uri_list(display, self.target, event.property)?;
+
+        if self.dropped.take().is_some() {
+            self.send_finished(display, true);
+
+            return Some(FileDrop::Dropped {
+                position: self.position,
+                paths,
+            });
+        }
+
+        self.paths = Some(paths.clone());
+
+        Some(FileDrop::Hovered {
+            position: self.position,
+            paths,
+        })
+    }
+
+    unsafe fn offers_uri_list(&self, display: *mut Display, event: &XClientMessageEvent) -> bool {
+        // more than three types are listed in the `XdndTypeList` of the source
+        if event.data.get_long(1) & 1 == 0 {
+            return (2..5).any(|i| event.data.get_long(i) as xlib::Atom == self.atoms.uri_list);
+        }
+
+        let mut kind = 0;
+        let mut format = 0;
+        let mut items = 0;
+        let mut remaining = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+
+        (XLIB.XGetWindowProperty)(
+            display,
+            self.source,
+            self.atoms.type_list,
+            0,
+            c_long::MAX / 4,
+            0,
+            xlib::XA_ATOM,
+            &mut kind,
+            &mut format,
+            &mut items,
+            &mut remaining,
+            &mut data,
+        );
+
+        if data.is_null() {
+            return false;
+        }
+
+        // format 32 properties are returned as longs
+        let types = slice::from_raw_parts(data as *const c_ulong, items as usize);
+        let offered = types.contains(&self.atoms.uri_list);
+
+        (XLIB.XFree)(data as *mut _);
+
+        offered
+    }
+
+    unsafe fn request_paths(&self, display: *mut Display, time: xlib::Time) {
+        (XLIB.XConvertSelection)(
+            display,
+            self.atoms.selection,
+            self.atoms.uri_list,
+            self.atoms.property,
+            self.target,
+            time,
+        );
+    }
+
+    unsafe fn send_status(&self, display: *mut Display) {
+        let action = match self.accepted {
+            true => self.atoms.action_copy as c_long,
+            false => 0,
+        };
+
+        self.send(
+            display,
+            self.atoms.status,
+            [0, self.accepted as c_long, 0, 0, action],
+        );
+    }
+
+    unsafe fn send_finished(&mut self, display: *mut Display, accepted: bool) {
+        let action = match accepted {
+            true => self.atoms.action_copy as c_long,
+            false => 0,
+        };
+
+        self.send(
+            display,
+            self.atoms.finished,
+            [0, accepted as c_long, action, 0, 0],
+        );
+        self.source = 0;
+    }
+
+    /// Send a message to the source, the first element of `data` is set to the target.
+    unsafe fn send(&self, display: *mut Display, message_type: xlib::Atom, data: [c_long; 5]) {
+        let mut event: XEvent = mem::zeroed();
+        let message = &mut event.client_message;
+
+        message.type_ = ClientMessage;
+        message.display = display;
+        message.window = self.source;
+        message.message_type = message_type;
+        message.format = 32;
+
+        message.data.set_long(0, self.target as c_long);
+
+        for (i, value) in data.iter().enumerate().skip(1) {
+            message.data.set_long(i, *value);
+        }
+
+        (XLIB.XSendEvent)(display, self.source, 0, 0, &mut event);
+        (XLIB.XFlush)(display);
+    }
+}
+
+/// Translate a position on the root window to a logical position in `window`.
+unsafe fn local_position(
+    display: *mut Display,
+    window: xlib::Window,
+    x: i32,
+    y: i32,
+    scale: f32,
+) -> Point {
+    let root = (XLIB.XDefaultRootWindow)(display);
+
+    let mut local_x = 0;
+    let mut local_y = 0;
+    let mut child = 0;
+
+    (XLIB.XTranslateCoordinates)(
+        display,
+        root,
+        window,
+        x,
+        y,
+        &mut local_x,
+        &mut local_y,
+        &mut child,
+    );
+
+    Point::new(local_x as f32, local_y as f32) / scale
+}
+
+unsafe fn read_uri_list(
+    display: *mut Display,
+    window: xlib::Window,
+    property: xlib::Atom,
+) -> Option<Vec<PathBuf>> {
+    let mut kind = 0;
+    let mut format = 0;
+    let mut items = 0;
+    let mut remaining = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+
+    (XLIB.XGetWindowProperty)(
+        display,
+        window,
+        property,
+        0,
+        c_long::MAX / 4,
+        1,
+        AnyPropertyType as c_ulong,
+        &mut kind,
+        &mut format,
+        &mut items,
+        &mut remaining,
+        &mut data,
+    );
+
+    if data.is_null() {
+        return None;
+    }
+
+    let list = parse_uri_list(slice::from_raw_parts(data, items as usize));
+
+    (XLIB.XFree)(data as *mut _);
+
+    Some(list)
+}
+
+/// Parse a `text/uri-list`, keeping the local files.
+///
+/// Paths are bytes on Linux, so they're built from the decoded bytes without assuming UTF-8.
+fn parse_uri_list(list: &[u8]) -> Vec<PathBuf> {
+    let lines = list.split(|&byte| byte == b'\n').map(<[u8]>::trim_ascii);
+    let uris = lines.filter(|line| !line.is_empty() && !line.starts_with(b"#"));
+
+    uris.filter_map(|uri| {
+        let path = uri.strip_prefix(b"file:")?;
+
+        // skip the host of `file://host/path`, `file:/path` has none
+        let path = match path.strip_prefix(b"//") {
+            Some(path) => &path[path.iter().position(|&byte| byte == b'/')?..],
+            None if path.starts_with(b"/") => path,
+            None => return None,
+        };
+
+        Some(PathBuf::from(OsString::from_vec(percent_decode(path))))
+    })
+    .collect()
+}
+
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut iter = input.iter().copied();
+
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let hex = [iter.next(), iter.next()];
+        let decoded = match hex {
+            [Some(high), Some(low)] => {
+                let hex = [high, low];
+                let hex = std::str::from_utf8(&hex).ok();
+                hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            }
+            _ => None,
+        };
+
+        match decoded {
+            Some(decoded) => bytes.push(decoded),
+            None => {
+                bytes.push(b'%');
+                bytes.extend(hex.into_iter().flatten());
+            }
+        }
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn uri_list_forms() {
+        let list = b"file:///home/user/a.wav\r\nfile://localhost/tmp/b.wav\r\nfile:/tmp/c.wav\r\n";
+
+        assert_eq!(
+            parse_uri_list(list),
+            [
+                Path::new("/home/user/a.wav"),
+                Path::new("/tmp/b.wav"),
+                Path::new("/tmp/c.wav"),
+            ]
+        );
+    }
+
+    #[test]
+    fn uri_list_skips_comments_and_remote_uris() {
+        let list = b"# a comment\n\nhttps://example.com/a.wav\nfile:relative.wav\nfile:///a.wav";
+
+        assert_eq!(parse_uri_list(list), [Path::new("/a.wav")]);
+    }
+
+    #[test]
+    fn uri_list_percent_decoding() {
+        let list = b"file:///tmp/a%20b%C3%A9.wav\nfile:///tmp/100%.wav\nfile:///tmp/%zz";
+
+        assert_eq!(
+            parse_uri_list(list),
+            [
+                Path::new("/tmp/a b\u{e9}.wav"),
+                Path::new("/tmp/100%.wav"),
+                Path::new("/tmp/%zz"),
+            ]
+        );
+    }
+
+    #[test]
+    fn uri_list_keeps_non_utf8_paths() {
+        let paths = parse_uri_list(b"file:///tmp/%FF.wav");
+
+        assert_eq!(paths, [Path::new(OsStr::from_bytes(b"/tmp/\xff.wav"))]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    ffi::{c_char, c_ulong, c_void, CStr},
+    ptr,
+};
+
+use x11_dl::xlib::{
+    self, Display, XBufferOverflow, XEvent, XIMPreeditNothing, XIMPreeditPosition,
+    XIMStatusNothing, XKeyEvent, XLookupBoth, XLookupChars, XNClientWindow_0, XNFocusWindow_0,
+    XNInputStyle_0, XNPreeditAttributes_0, XNSpotLocation_0, XPoint,
+};
+
+use super::XLIB;
+
+/// Use the locale of the user for text input, unless the host already set a locale.
+///
+/// Input methods only produce UTF-8 text in a UTF-8 locale, and a process starts in the "C"
+/// locale.
+unsafe fn set_locale() {
+    let current = libc::setlocale(libc::LC_CTYPE, ptr::null());
+
+    if current.is_null() || CStr::from_ptr(current) == c"C" {
+        libc::setlocale(libc::LC_CTYPE, c"".as_ptr());
+    }
+}
+
+/// An X input method, used for composed characters, dead keys and IME input.
+pub struct InputMethod {
+    im: xlib::XIM,
+}
+
+impl InputMethod {
+    /// Open the input method of the user, configured by `XMODIFIERS`.
+    pub unsafe fn open(display: *mut Display) -> Option<Self> {
+        set_locale();
+        (XLIB.XSetLocaleModifiers)(c"".as_ptr());
+
+        let im = (XLIB.XOpenIM)(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+
+        match im.is_null() {
+            true => None,
+            false => Some(Self { im }),
+        }
+    }
+
+    /// Create an input context for `window`.
+    ///
+    /// Over-the-spot preedit is preferred, so the IME window follows the text cursor.
+    pub unsafe fn create_context(&self, window: xlib::Window) -> Option<InputContext> {
+        let styles = [
+            XIMPreeditPosition | XIMStatusNothing,
+            XIMPreeditNothing | XIMStatusNothing,
+        ];
+
+        for style in styles {
+            let ic = (XLIB.XCreateIC)(
+                self.im,
+                XNInputStyle_0.as_ptr() as *const c_char,
+                style as c_ulong,
+                XNClientWindow_0.as_ptr() as *const c_char,
+                window,
+                XNFocusWindow_0.as_ptr() as *const c_char,
+                window,
+                ptr::null_mut::<c_void>(),
+            );
+
+            if !ic.is_null() {
+                return Some(InputContext { ic, style });
+            }
+        }
+
+        None
+    }
+}
+
+impl Drop for InputMethod {
+    fn drop(&mut self) {
+        unsafe { (XLIB.XCloseIM)(self.im) };
+    }
+}
+
+/// An X input context of a window.
+pub struct InputContext {
+    ic: xlib::XIC,
+    style: c_ulong,
+}
+
+impl InputContext {
+    /// Set whether the window has keyboard focus.
+    pub unsafe fn set_focus(&self, focused: bool) {
+        match focused {
+            true => (XLIB.XSetICFocus)(self.ic),
+            false => (XLIB.XUnsetICFocus)(self.ic),
+        }
+    }
+
+    /// Position the preedit window of the IME at `x`, `y` in window coordinates.
+    pub unsafe fn set_spot(&self, x: i16, y: i16) {
+        if self.style & XIMPreeditPosition == 0 {
+            return;
+        }
+
+        let mut spot = XPoint { x, y };
+
+        let attributes = (XLIB.XVaCreateNestedList)(
+            0,
+            XNSpotLocation_0.as_ptr() as *const c_char,
+            &mut spot as *mut XPoint,
+            ptr::null_mut::<c_void>(),
+        );
+
+        (XLIB.XSetICValues)(
+            self.ic,
+            XNPreeditAttributes_0.as_ptr() as *const c_char,
+            attributes,
+            ptr::null_mut::<c_void>(),
+        );
+
+        (XLIB.XFree)(attributes);
+    }
+
+    /// Look up the text committed by a key press, this may be more than one character.
+    pub unsafe fn lookup(&self, key: &mut XKeyEvent) -> Option<String> {
+        let mut buffer = vec![0u8; 64];
+        let mut keysym = 0;
+        let mut status = 0;
+
+        loop {
+            let count = (XLIB.Xutf8LookupString)(
+                self.ic,
+                key,
+                buffer.as_mut_ptr() as *mut c_char,
+                buffer.len() as i32,
+                &mut keysym,
+                &mut status,
+            );
+
+            if status == XBufferOverflow {
+                buffer.resize(count as usize, 0);
+                continue;
+            }
+
+            return match status {
+                XLookupChars | XLookupBoth if count > 0 => {
+                    let text = String::from_utf8_lossy(&buffer[..count as usize]);
+                    Some(text.into_owned())
+                }
+                _ => None,
+            };
+        }
+    }
+}
+
+impl Drop for InputContext {
+    fn drop(&mut self) {
+        unsafe { (XLIB.XDestroyIC)(self.ic) };
+    }
+}
+
+/// Let the input method process `event`, returns true if the event should be ignored.
+pub unsafe fn filter_event(event: &mut XEvent) -> bool {
+    (XLIB.XFilterEvent)(event, 0) != 0
+}
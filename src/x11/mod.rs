@@ -0,0 +1,1824 @@
+mod clipboard;
+mod dnd;
+mod ime;
+mod run_loop;
+mod software;
+
+use std::{
+    collections::HashMap,
+    ffi,
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, LazyLock, OnceLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use ori::{
+    app::{AppRequest, UiBuilder},
+    core::{clipboard::Clipboard, command::CommandWaker, window::WindowUpdate},
+    prelude::*,
+};
+use ori_skia::SkiaRenderer;
+use parking_lot::Mutex;
+use vst3_sys::vst::{IComponentHandler, RestartFlags};
+use x11_dl::{
+    glx::{
+        self, Glx, GLX_ALPHA_SIZE, GLX_BLUE_SIZE, GLX_DOUBLEBUFFER, GLX_GREEN_SIZE, GLX_RED_SIZE,
+        GLX_RGBA, GLX_SAMPLES, GLX_SAMPLE_BUFFERS,
+    },
+    xcursor::Xcursor,
+    xlib::{
+        self, AllocNone, ButtonPressMask, ButtonReleaseMask, CWColormap, CWEventMask,
+        CWOverrideRedirect, Display, EnterWindowMask, ExposureMask, FocusChangeMask, InputOutput,
+        KeyPressMask, KeyReleaseMask, LeaveWindowMask, PointerMotionMask, StructureNotifyMask,
+        XEvent, XKeyEvent, XSetWindowAttributes, Xlib,
+    },
+    xrandr::Xrandr,
+};
+use xkeysym::Keysym;
+
+use clipboard::{Selections, X11Clipboard};
+use dnd::Dnd;
+use ime::{InputContext, InputMethod};
+use software::SoftwareRenderer;
+
+pub(crate) use run_loop::IRunLoop;
+
+use crate::{
+    editor::{EditorHandle, EditorUi},
+    EditorOptions, FileDrop, FrameRate, PlugFrame, PluginState, Redraw, VstPlugin,
+};
+
+/// Xlib, initialized for threads before the first display is opened, as the editor and event
+/// threads share a display.
+static XLIB: LazyLock<Xlib> = LazyLock::new(|| {
+    let xlib = Xlib::open().unwrap();
+    unsafe { (xlib.XInitThreads)() };
+    xlib
+});
+static XCURSOR: LazyLock<Xcursor> = LazyLock::new(|| Xcursor::open().unwrap());
+static GLX: LazyLock<Option<Glx>> = LazyLock::new(|| Glx::open().ok());
+
+/// How often the host's run loop drives the editor, and forwards resizes of the editor thread.
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The frame rate used when the display refresh rate is unknown.
+const DEFAULT_FRAME_RATE: f32 = 60.0;
+
+/// The highest frame rate the editor animates at, so an unbounded rate can't busy-loop.
+const MAX_FRAME_RATE: f32 = 480.0;
+
+/// Spawn the editor, `scale` is the content scale set by the host if any.
+pub unsafe fn spawn_editor<P: VstPlugin>(
+    state: Arc<PluginState<P>>,
+    parent: *mut ffi::c_void,
+    scale: Option<f32>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+) -> Arc<dyn EditorHandle> {
+    let ui: EditorUi<P> = |plugin| any(plugin.ui());
+    spawn_editor_with(state, parent, scale, frame, P::window(), ui)
+}
+
+/// Spawn the editor in a top-level window, outside of a host.
+///
+/// The returned thread finishes when the window is closed.
+pub unsafe fn spawn_preview<P: VstPlugin>(
+    state: Arc<PluginState<P>>,
+    window: Window,
+    ui: EditorUi<P>,
+) -> JoinHandle<()> {
+    let (event_tx, event_rx) = mpsc::channel();
+    let scale = system_scale();
+
+    let handle = Arc::new(X11EditorHandle::new(event_tx, &window, scale, P::editor()));
+    let frame = Arc::new(Mutex::new(None));
+
+    spawn_editor_thread(state, frame, ptr::null_mut(), event_rx, handle, window, ui)
+}
+
+/// Spawn the editor as a child of `parent`, or of the root window if `parent` is null.
+unsafe fn spawn_editor_with<P: VstPlugin>(
+    state: Arc<PluginState<P>>,
+    parent: *mut ffi::c_void,
+    scale: Option<f32>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+    window: Window,
+    ui: EditorUi<P>,
+) -> Arc<dyn EditorHandle> {
+    let (event_tx, event_rx) = mpsc::channel();
+
+    let scale = scale.unwrap_or_else(|| system_scale());
+    let handle = Arc::new(X11EditorHandle::new(event_tx, &window, scale, P::editor()));
+
+    let run_loop = match handle.options.run_loop {
+        true => frame.lock().as_ref().and_then(|frame| frame.run_loop()),
+        false => None,
+    };
+
+    if let Some(run_loop) = run_loop {
+        let display = (XLIB.XOpenDisplay)(ptr::null());
+        let interval = frame_interval(display, handle.options.frame_rate).min(POLL_INTERVAL);
+
+        match run_loop::register::<P>(run_loop, display, interval) {
+            Some(registration) => {
+                let editor =
+                    create_editor(display, parent, state, frame, handle.clone(), window, ui);
+                registration.attach(editor, event_rx);
+
+                return handle;
+            }
+            None => {
+                // nothing was created on the display yet, so it's safe to close
+                (XLIB.XCloseDisplay)(display);
+                warn!("The host refused the run loop handlers, using an editor thread");
+            }
+        }
+    }
+
+    // the frame may only be used from the host's UI thread, so resizes go through its run loop
+    if let Some(run_loop) = frame.lock().as_ref().and_then(|frame| frame.run_loop()) {
+        let timer =
+            run_loop::register_resize(run_loop, handle.clone(), frame.clone(), POLL_INTERVAL);
+        *handle.resize_timer.lock() = timer;
+    }
+
+    spawn_editor_thread(state, frame, parent, event_rx, handle.clone(), window, ui);
+
+    handle
+}
+
+/// The size is in physical pixels, the size of the ori window is `size / scale`.
+struct X11EditorHandle {
+    event_tx: Sender<EditorEvent>,
+    width: AtomicU32,
+    height: AtomicU32,
+    scale: AtomicU32,
+    resizable: AtomicBool,
+    options: EditorOptions,
+
+    /// Runs the editor in place, set when the editor runs on the host's run loop.
+    pump: OnceLock<Box<dyn Fn() + Send + Sync>>,
+
+    /// Forwards resizes to the host's UI thread, set when the editor runs on its own thread.
+    resize_timer: Mutex<Option<run_loop::ResizeTimer>>,
+
+    /// The physical size requested by the editor thread, waiting for `resize_timer`.
+    pending_resize: Mutex<Option<(u32, u32)>>,
+
+    /// Whether the key forwarded by the host was handled, only known on the run loop.
+    key_handled: AtomicBool,
+
+    /// Whether the wheel forwarded by the host was handled, see `key_handled`.
+    wheel_handled: AtomicBool,
+}
+
+impl X11EditorHandle {
+    fn new(
+        event_tx: Sender<EditorEvent>,
+        window: &Window,
+        scale: f32,
+        options: EditorOptions,
+    ) -> Self {
+        Self {
+            event_tx,
+            width: AtomicU32::new(physical(window.width(), scale)),
+            height: AtomicU32::new(physical(window.height(), scale)),
+            scale: AtomicU32::new(scale.to_bits()),
+            resizable: AtomicBool::new(window.resizable),
+            options,
+            pump: OnceLock::new(),
+            resize_timer: Mutex::new(None),
+            pending_resize: Mutex::new(None),
+            key_handled: AtomicBool::new(false),
+            wheel_handled: AtomicBool::new(false),
+        }
+    }
+
+    /// Handle the queued events right away, if the editor runs on the host's run loop.
+    fn pump(&self) {
+        if let Some(pump) = self.pump.get() {
+            pump();
+        }
+    }
+}
+
+impl EditorHandle for X11EditorHandle {
+    fn quit(&self) {
+        if let Some(timer) = self.resize_timer.lock().take() {
+            unsafe { timer.unregister() };
+        }
+
+        let _ = self.event_tx.send(EditorEvent::Quit);
+        self.pump();
+    }
+
+    fn size(&self) -> (u32, u32) {
+        let width = self.width.load(Ordering::Relaxed);
+        let height = self.height.load(Ordering::Relaxed);
+
+        (width, height)
+    }
+
+    fn resize(&self, width: u32, height: u32) {
+        let _ = self.event_tx.send(EditorEvent::Resize(width, height));
+        self.pump();
+    }
+
+    fn resizable(&self) -> bool {
+        self.resizable.load(Ordering::Relaxed)
+    }
+
+    fn rebuild(&self) {
+        let _ = self.event_tx.send(EditorEvent::Rebuild);
+    }
+
+    fn scale(&self) -> f32 {
+        f32::from_bits(self.scale.load(Ordering::Relaxed))
+    }
+
+    fn set_scale(&self, scale: f32) {
+        let _ = self.event_tx.send(EditorEvent::Scale(scale));
+        self.pump();
+    }
+
+    fn key(&self, key: u16, key_code: i16, modifiers: i16, pressed: bool) -> bool {
+        let event = EditorEvent::HostKey {
+            key,
+            key_code,
+            modifiers,
+            pressed,
+        };
+
+        self.key_handled.store(false, Ordering::Relaxed);
+
+        if self.event_tx.send(event).is_err() {
+            return false;
+        }
+
+        // the editor thread would make the host wait, so keys aren't claimed there
+        match self.pump.get() {
+            Some(pump) => pump(),
+            None => return false,
+        }
+
+        // false if the editor was busy and the key is handled after returning to the host
+        self.key_handled.load(Ordering::Relaxed)
+    }
+
+    fn wheel(&self, distance: f32) -> bool {
+        let event = EditorEvent::HostWheel { distance };
+
+        self.wheel_handled.store(false, Ordering::Relaxed);
+
+        if self.event_tx.send(event).is_err() {
+            return false;
+        }
+
+        match self.pump.get() {
+            Some(pump) => pump(),
+            None => return false,
+        }
+
+        self.wheel_handled.load(Ordering::Relaxed)
+    }
+
+    fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+        if !self.resizable() {
+            return self.size();
+        }
+
+        let scale = self.scale();
+        let (width, height) = (self.options).constrain(width as f32 / scale, height as f32 / scale);
+
+        let width = (width * scale).round() as u32;
+        let height = (height * scale).round() as u32;
+
+        (width, height)
+    }
+}
+
+fn physical(logical: u32, scale: f32) -> u32 {
+    (logical as f32 * scale).round() as u32
+}
+
+fn logical(physical: u32, scale: f32) -> u32 {
+    (physical as f32 / scale).round() as u32
+}
+
+/// Get the scale of the desktop, from `GDK_SCALE` or `Xft.dpi`.
+unsafe fn system_scale() -> f32 {
+    if let Some(scale) = std::env::var("GDK_SCALE")
+        .ok()
+        .and_then(|scale| scale.parse::<f32>().ok())
+        .filter(|scale| *scale > 0.0)
+    {
+        return scale;
+    }
+
+    let display = (XLIB.XOpenDisplay)(ptr::null());
+
+    if display.is_null() {
+        return 1.0;
+    }
+
+    let dpi = xft_dpi(display);
+    (XLIB.XCloseDisplay)(display);
+
+    dpi.map_or(1.0, |dpi| dpi / 96.0)
+}
+
+unsafe fn xft_dpi(display: *mut Display) -> Option<f32> {
+    let resources = (XLIB.XResourceManagerString)(display);
+
+    if resources.is_null() {
+        return None;
+    }
+
+    (XLIB.XrmInitialize)();
+    let database = (XLIB.XrmGetStringDatabase)(resources);
+
+    if database.is_null() {
+        return None;
+    }
+
+    let mut kind = ptr::null_mut();
+    let mut value = MaybeUninit::<xlib::XrmValue>::zeroed();
+
+    let found = (XLIB.XrmGetResource)(
+        database,
+        c"Xft.dpi".as_ptr(),
+        c"Xft.Dpi".as_ptr(),
+        &mut kind,
+        value.as_mut_ptr(),
+    );
+
+    let value = value.assume_init();
+
+    let dpi = match found != 0 && !value.addr.is_null() {
+        true => ffi::CStr::from_ptr(value.addr).to_str().ok(),
+        false => None,
+    };
+
+    let dpi = dpi.and_then(|dpi| dpi.trim().parse::<f32>().ok());
+    (XLIB.XrmDestroyDatabase)(database);
+
+    dpi.filter(|dpi| *dpi > 0.0)
+}
+
+#[allow(dead_code)]
+struct X11Editor<P: VstPlugin> {
+    parent: *mut ffi::c_void,
+    display: *mut Display,
+    event_thread: Option<JoinHandle<()>>,
+    handle: Arc<X11EditorHandle>,
+    state: Arc<PluginState<P>>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+
+    params: Vec<f32>,
+
+    app: App<P>,
+    windows: Vec<X11Window>,
+    wm_protocols: xlib::Atom,
+    wm_delete_window: xlib::Atom,
+    im: Option<InputMethod>,
+    selections: Arc<Mutex<Selections>>,
+    dnd: Dnd,
+
+    render: bool,
+    running: Arc<AtomicBool>,
+    scale: f32,
+
+    frame_interval: Duration,
+    next_frame: Instant,
+}
+
+/// A window of the editor, the first window is embedded in the host.
+struct X11Window {
+    id: WindowId,
+    window: xlib::Window,
+    colormap: xlib::Colormap,
+    width: u32,
+    height: u32,
+    cursor: Cursor,
+    cursors: HashMap<Cursor, xlib::Cursor>,
+    renderer: Renderer,
+    ic: Option<InputContext>,
+}
+
+enum Renderer {
+    Gl {
+        glx: glx::GLXContext,
+        skia: ManuallyDrop<SkiaRenderer>,
+    },
+    Software(SoftwareRenderer),
+}
+
+impl<P: VstPlugin> X11Editor<P> {
+    /// The window embedded in the host.
+    fn main_window(&self) -> Option<&X11Window> {
+        self.windows.first()
+    }
+
+    fn window_by_id(&mut self, id: WindowId) -> Option<&mut X11Window> {
+        self.windows.iter_mut().find(|window| window.id == id)
+    }
+
+    fn is_main_window(&self, id: WindowId) -> bool {
+        self.main_window().is_some_and(|window| window.id == id)
+    }
+}
+
+unsafe fn handle_app_requests<P: VstPlugin>(editor: &mut X11Editor<P>) {
+    for request in editor.app.take_requests() {
+        handle_app_request(editor, request);
+    }
+}
+
+unsafe fn handle_app_request<P: VstPlugin>(editor: &mut X11Editor<P>, request: AppRequest<P>) {
+    match request {
+        AppRequest::OpenWindow(window, ui) => open_window(editor, window, ui),
+        AppRequest::CloseWindow(id) => close_window(editor, id),
+        AppRequest::DragWindow(_) => {
+            warn!("DragWindow is not supported on X11");
+        }
+        AppRequest::RequestRedraw(_) => editor.render = true,
+        AppRequest::UpdateWindow(id, update) => match update {
+            WindowUpdate::Title(_) => warn!("Title is not supported in VSTs"),
+            WindowUpdate::Icon(_) => warn!("Icon is not supported in VSTs"),
+            WindowUpdate::Size(size) if !editor.is_main_window(id) => {
+                resize_secondary_window(editor, id, size);
+            }
+            WindowUpdate::Size(size) => resize_window(editor, size),
+            WindowUpdate::Scale(scale) => set_scale(editor, scale),
+            WindowUpdate::Resizable(resizable) => {
+                editor.handle.resizable.store(resizable, Ordering::Relaxed);
+            }
+            WindowUpdate::Decorated(_) => warn!("Decorated is not supported in VSTs"),
+            WindowUpdate::Maximized(_) => warn!("Maximized is not supported in VSTs"),
+            WindowUpdate::Visible(_) => warn!("Visible is not supported in VSTs"),
+            WindowUpdate::Color(_) => editor.render = true,
+            WindowUpdate::Cursor(cursor) => {
+                set_window_cursor(editor, id, cursor);
+            }
+            WindowUpdate::Ime(ime) => set_ime(editor, id, ime),
+        },
+        AppRequest::Quit => editor.running.store(false, Ordering::Relaxed),
+    }
+}
+
+/// Open a window, the first window is embedded in the host and the rest are top-level.
+///
+/// Undecorated windows, like popups and tooltips, are override-redirect, and decorated windows
+/// are transient for the host window.
+unsafe fn open_window<P: VstPlugin>(editor: &mut X11Editor<P>, window: Window, ui: UiBuilder<P>) {
+    let is_main = editor.windows.is_empty();
+
+    let vi = match editor.handle.options.software {
+        true => ptr::null_mut(),
+        false => choose_gl_visual(editor.display),
+    };
+
+    // without a GLX visual the editor falls back to rendering on the CPU
+    let (visual, depth) = match vi.is_null() {
+        true => {
+            let screen = (XLIB.XDefaultScreen)(editor.display);
+            let visual = (XLIB.XDefaultVisual)(editor.display, screen);
+            let depth = (XLIB.XDefaultDepth)(editor.display, screen);
+
+            (visual, depth)
+        }
+        false => ((*vi).visual, (*vi).depth),
+    };
+
+    let parent = match is_main {
+        true => editor.parent as xlib::Window,
+        false => (XLIB.XDefaultRootWindow)(editor.display),
+    };
+
+    let colormap = (XLIB.XCreateColormap)(editor.display, parent, visual, AllocNone);
+
+    let mut attrs = MaybeUninit::<XSetWindowAttributes>::zeroed();
+
+    (*attrs.as_mut_ptr()).colormap = colormap;
+    (*attrs.as_mut_ptr()).override_redirect = (!is_main && !window.decorated) as i32;
+    (*attrs.as_mut_ptr()).event_mask = ExposureMask
+        | StructureNotifyMask
+        | PointerMotionMask
+        | EnterWindowMask
+        | LeaveWindowMask
+        | ButtonPressMask
+        | ButtonReleaseMask
+        | KeyPressMask
+        | KeyReleaseMask
+        | FocusChangeMask;
+
+    let width = physical(window.width(), editor.scale);
+    let height = physical(window.height(), editor.scale);
+
+    if is_main {
+        editor.handle.width.store(width, Ordering::Relaxed);
+        editor.handle.height.store(height, Ordering::Relaxed);
+    }
+
+    let (x, y) = match is_main {
+        true => (0, 0),
+        false => secondary_position(editor, &window, width, height),
+    };
+
+    let x11_window = (XLIB.XCreateWindow)(
+        editor.display,
+        parent,
+        x,
+        y,
+        width,
+        height,
+        0,
+        depth,
+        InputOutput as u32,
+        visual,
+        CWColormap | CWEventMask | CWOverrideRedirect,
+        attrs.as_mut_ptr(),
+    );
+
+    // the main window of the preview is a child of the root window, and top-level as well
+    let root = (XLIB.XDefaultRootWindow)(editor.display);
+    let top_level = !is_main || editor.parent as xlib::Window == root;
+
+    if top_level {
+        let title = ffi::CString::new(window.title.as_str()).unwrap_or_default();
+        (XLIB.XStoreName)(editor.display, x11_window, title.as_ptr());
+
+        if !is_main {
+            (XLIB.XSetTransientForHint)(editor.display, x11_window, editor.parent as xlib::Window);
+        }
+
+        let mut protocols = [editor.wm_delete_window];
+        (XLIB.XSetWMProtocols)(editor.display, x11_window, protocols.as_mut_ptr(), 1);
+    }
+
+    (XLIB.XMapWindow)(editor.display, x11_window);
+
+    // windows share the resources of the first GL context
+    let share = editor
+        .windows
+        .iter()
+        .find_map(|window| match window.renderer {
+            Renderer::Gl { glx, .. } => Some(glx),
+            Renderer::Software(_) => None,
+        });
+
+    let renderer = match vi.is_null() {
+        true => {
+            let software = SoftwareRenderer::new(editor.display, x11_window, visual, depth);
+            Renderer::Software(software)
+        }
+        false => {
+            let renderer = create_gl_renderer(editor.display, x11_window, vi, share);
+            (XLIB.XFree)(vi as *mut _);
+            renderer
+        }
+    };
+
+    let ic = match editor.im {
+        Some(ref im) => im.create_context(x11_window),
+        None => None,
+    };
+
+    if is_main {
+        editor.selections.lock().set_window(x11_window);
+    }
+
+    editor.dnd.set_aware(editor.display, x11_window);
+
+    let x11_window = X11Window {
+        id: window.id(),
+        window: x11_window,
+        colormap,
+        width,
+        height,
+        cursor: Cursor::default(),
+        cursors: HashMap::new(),
+        renderer,
+        ic,
+    };
+
+    let id = window.id();
+
+    let mut plugin = editor.state.plugin.lock();
+    editor.app.add_window(&mut plugin, ui, window);
+    editor.app.window_scaled(&mut plugin, id, editor.scale);
+
+    editor.windows.push(x11_window);
+}
+
+/// Close a window, closing the window embedded in the host quits the editor.
+unsafe fn close_window<P: VstPlugin>(editor: &mut X11Editor<P>, id: WindowId) {
+    if editor.is_main_window(id) {
+        editor.running.store(false, Ordering::Relaxed);
+        return;
+    }
+
+    let Some(index) = editor.windows.iter().position(|window| window.id == id) else {
+        return;
+    };
+
+    let mut window = editor.windows.remove(index);
+    destroy_window(editor.display, &mut window);
+
+    let mut plugin = editor.state.plugin.lock();
+    editor.app.remove_window(&mut plugin, id);
+}
+
+unsafe fn destroy_window(display: *mut Display, window: &mut X11Window) {
+    // the input context must be destroyed before its window
+    window.ic.take();
+
+    match window.renderer {
+        Renderer::Gl { glx, ref mut skia } => {
+            ManuallyDrop::drop(skia);
+
+            let glx_lib = GLX.as_ref().unwrap();
+            (glx_lib.glXDestroyContext)(display, glx);
+        }
+        Renderer::Software(ref mut software) => software.destroy(display),
+    }
+
+    (XLIB.XDestroyWindow)(display, window.window);
+    (XLIB.XFreeColormap)(display, window.colormap);
+
+    for (_, cursor) in window.cursors.drain() {
+        (XLIB.XFreeCursor)(display, cursor);
+    }
+}
+
+/// The position of a secondary window on the root window, in physical pixels.
+///
+/// Popups, like dropdowns and tooltips, open at the pointer, and decorated windows are centered
+/// on the editor.
+unsafe fn secondary_position<P: VstPlugin>(
+    editor: &X11Editor<P>,
+    window: &Window,
+    width: u32,
+    height: u32,
+) -> (i32, i32) {
+    if !window.decorated {
+        if let Some(position) = pointer_position(editor.display) {
+            return position;
+        }
+    }
+
+    let Some(main) = editor.main_window() else {
+        return (0, 0);
+    };
+
+    let (x, y) = root_position(editor.display, main.window);
+
+    let x = x + (main.width as i32 - width as i32) / 2;
+    let y = y + (main.height as i32 - height as i32) / 2;
+
+    (x.max(0), y.max(0))
+}
+
+/// The position of the pointer on the root window.
+unsafe fn pointer_position(display: *mut Display) -> Option<(i32, i32)> {
+    let root = (XLIB.XDefaultRootWindow)(display);
+
+    let mut root_return = 0;
+    let mut child = 0;
+    let mut x = 0;
+    let mut y = 0;
+    let mut window_x = 0;
+    let mut window_y = 0;
+    let mut mask = 0;
+
+    let same_screen = (XLIB.XQueryPointer)(
+        display,
+        root,
+        &mut root_return,
+        &mut child,
+        &mut x,
+        &mut y,
+        &mut window_x,
+        &mut window_y,
+        &mut mask,
+    );
+
+    (same_screen != 0).then_some((x, y))
+}
+
+/// The position of the top-left corner of `window` on the root window.
+unsafe fn root_position(display: *mut Display, window: xlib::Window) -> (i32, i32) {
+    let root = (XLIB.XDefaultRootWindow)(display);
+
+    let mut x = 0;
+    let mut y = 0;
+    let mut child = 0;
+
+    (XLIB.XTranslateCoordinates)(display, window, root, 0, 0, &mut x, &mut y, &mut child);
+
+    (x, y)
+}
+
+/// Choose a multisampled GLX visual, returns null if GLX is unavailable.
+unsafe fn choose_gl_visual(display: *mut Display) -> *mut xlib::XVisualInfo {
+    let Some(ref glx) = *GLX else {
+        return ptr::null_mut();
+    };
+
+    let mut attrs = [
+        GLX_RGBA,
+        GLX_RED_SIZE,
+        8,
+        GLX_BLUE_SIZE,
+        8,
+        GLX_GREEN_SIZE,
+        8,
+        GLX_ALPHA_SIZE,
+        8,
+        GLX_DOUBLEBUFFER,
+        GLX_SAMPLE_BUFFERS,
+        1,
+        GLX_SAMPLES,
+        4,
+        0,
+    ];
+
+    (glx.glXChooseVisual)(display, 0, attrs.as_mut_ptr())
+}
+
+unsafe fn create_gl_renderer(
+    display: *mut Display,
+    window: xlib::Window,
+    vi: *mut xlib::XVisualInfo,
+    share: Option<glx::GLXContext>,
+) -> Renderer {
+    let glx = GLX.as_ref().unwrap();
+
+    let share = share.unwrap_or(ptr::null_mut());
+    let context = (glx.glXCreateContext)(display, vi, share, 1);
+    (glx.glXMakeCurrent)(display, window, context);
+
+    let skia = SkiaRenderer::new(|s| {
+        if s.starts_with("egl") {
+            return ptr::null();
+        }
+
+        let cstring = ffi::CString::new(s).unwrap();
+        (glx.glXGetProcAddress)(cstring.as_ptr() as *const _).unwrap() as *const _
+    });
+
+    (glx.glXMakeCurrent)(display, 0, ptr::null_mut());
+
+    Renderer::Gl {
+        glx: context,
+        skia: ManuallyDrop::new(skia),
+    }
+}
+
+/// Resize the window to a logical size requested by the plugin.
+///
+/// The resize goes through the host when possible, which calls `IPlugView::on_size`. On the
+/// editor thread the host is asked from its run loop, see `ResizeTimer`.
+unsafe fn resize_window<P: VstPlugin>(editor: &mut X11Editor<P>, size: Size) {
+    let width = physical(size.width.round() as u32, editor.scale);
+    let height = physical(size.height.round() as u32, editor.scale);
+
+    let (width, height) = match editor.handle.resizable() {
+        true => editor.handle.constrain(width, height),
+        false => (width, height),
+    };
+
+    if editor.handle.resize_timer.lock().is_some() {
+        *editor.handle.pending_resize.lock() = Some((width, height));
+        return;
+    }
+
+    // only the run loop editor runs on the host's UI thread
+    if editor.handle.pump.get().is_some() {
+        // don't hold the lock while the host calls back into the view
+        let frame = editor.frame.lock().clone();
+
+        if let Some(frame) = frame {
+            if frame.resize_view(width, height) {
+                return;
+            }
+        }
+    }
+
+    handle_event(editor, EditorEvent::Resize(width, height));
+}
+
+/// Resize a window other than the one embedded in the host to a logical size.
+unsafe fn resize_secondary_window<P: VstPlugin>(
+    editor: &mut X11Editor<P>,
+    id: WindowId,
+    size: Size,
+) {
+    let width = physical(size.width.round() as u32, editor.scale);
+    let height = physical(size.height.round() as u32, editor.scale);
+
+    let display = editor.display;
+
+    if let Some(window) = editor.window_by_id(id) {
+        (XLIB.XResizeWindow)(display, window.window, width.max(1), height.max(1));
+    }
+}
+
+/// Enable or disable the IME, and move its preedit window to the text cursor.
+unsafe fn set_ime<P: VstPlugin>(editor: &mut X11Editor<P>, id: WindowId, ime: Option<Ime>) {
+    let scale = editor.scale;
+
+    let Some(window) = editor.window_by_id(id) else {
+        return;
+    };
+
+    let Some(ref ic) = window.ic else {
+        return;
+    };
+
+    match ime {
+        Some(ime) => {
+            let x = ime.cursor.min.x * scale;
+            let y = ime.cursor.max.y * scale;
+
+            ic.set_focus(true);
+            ic.set_spot(x as i16, y as i16);
+        }
+        None => ic.set_focus(false),
+    }
+}
+
+unsafe fn set_scale<P: VstPlugin>(editor: &mut X11Editor<P>, scale: f32) {
+    if scale <= 0.0 || scale == editor.scale {
+        return;
+    }
+
+    let width = editor.handle.width.load(Ordering::Relaxed);
+    let height = editor.handle.height.load(Ordering::Relaxed);
+
+    // keep the logical size, so the editor grows with the scale
+    let size = Size::new(
+        logical(width, editor.scale) as f32,
+        logical(height, editor.scale) as f32,
+    );
+
+    let old_scale = editor.scale;
+
+    editor.scale = scale;
+    editor
+        .handle
+        .scale
+        .store(scale.to_bits(), Ordering::Relaxed);
+
+    for (i, window) in editor.windows.iter().enumerate() {
+        let mut plugin = editor.state.plugin.lock();
+        (editor.app).window_scaled(&mut plugin, window.id, scale);
+
+        // the main window is resized below, through the host
+        if i > 0 {
+            let width = physical(logical(window.width, old_scale), scale);
+            let height = physical(logical(window.height, old_scale), scale);
+
+            (XLIB.XResizeWindow)(editor.display, window.window, width, height);
+        }
+    }
+
+    resize_window(editor, size);
+}
+
+unsafe fn render_windows<P: VstPlugin>(editor: &mut X11Editor<P>) {
+    if !editor.render {
+        return;
+    }
+
+    editor.render = false;
+
+    for i in 0..editor.windows.len() {
+        render_window(editor, i);
+    }
+}
+
+unsafe fn render_window<P: VstPlugin>(editor: &mut X11Editor<P>, index: usize) {
+    let window = &mut editor.windows[index];
+
+    let draw = {
+        // we want to hold the lock for as short as possible
+        let mut plugin = editor.state.plugin.lock();
+        editor.app.draw_window(&mut plugin, window.id)
+    };
+
+    let width = window.width;
+    let height = window.height;
+
+    let fonts = editor.app.contexts.get_mut::<Box<dyn Fonts>>().unwrap();
+    let fonts = fonts.downcast_mut().unwrap();
+
+    match window.renderer {
+        Renderer::Gl { glx, ref mut skia } => {
+            let glx_lib = GLX.as_ref().unwrap();
+            (glx_lib.glXMakeCurrent)(editor.display, window.window, glx);
+
+            if let Some(draw) = draw {
+                skia.render(
+                    fonts,
+                    &draw.canvas,
+                    draw.clear_color,
+                    width,
+                    height,
+                    editor.scale,
+                );
+            }
+
+            (glx_lib.glXSwapBuffers)(editor.display, window.window);
+            (glx_lib.glXMakeCurrent)(editor.display, 0, ptr::null_mut());
+        }
+        Renderer::Software(ref mut software) => {
+            if let Some(draw) = draw {
+                software.render(
+                    editor.display,
+                    window.window,
+                    fonts,
+                    &draw.canvas,
+                    draw.clear_color,
+                    width,
+                    height,
+                    editor.scale,
+                );
+            }
+        }
+    }
+}
+
+unsafe fn set_window_cursor<P: VstPlugin>(editor: &mut X11Editor<P>, id: WindowId, cursor: Cursor) {
+    let display = editor.display;
+
+    if let Some(window) = editor.window_by_id(id) {
+        let cursor = window.cursors.entry(cursor).or_insert_with(|| {
+            let cstring = ffi::CString::new(cursor.name()).unwrap();
+            (XCURSOR.XcursorLibraryLoadCursor)(display, cstring.as_ptr())
+        });
+
+        (XLIB.XDefineCursor)(display, window.window, *cursor);
+    }
+}
+
+unsafe fn spawn_editor_thread<P: VstPlugin>(
+    state: Arc<PluginState<P>>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+    parent: *mut ffi::c_void,
+    event_rx: Receiver<EditorEvent>,
+    handle: Arc<X11EditorHandle>,
+    window: Window,
+    ui: EditorUi<P>,
+) -> JoinHandle<()> {
+    let parent = AssertSend(parent);
+
+    thread::spawn(move || {
+        let parent = parent;
+        let AssertSend(parent) = parent;
+
+        let display = (XLIB.XOpenDisplay)(ptr::null());
+
+        let running = Arc::new(AtomicBool::new(true));
+        let event_thread = spawn_event_thread(display, handle.event_tx.clone(), running.clone());
+
+        let redraw = state.host.redraw();
+        let wake_thread =
+            spawn_wake_thread(redraw.clone(), handle.event_tx.clone(), running.clone());
+
+        let mut editor = create_editor(display, parent, state, frame, handle, window, ui);
+        editor.event_thread = Some(event_thread);
+        editor.running = running;
+
+        // without the wake thread, redraws requested by the audio thread are polled
+        let poll = wake_thread.is_none().then_some(POLL_INTERVAL);
+
+        while editor.running.load(Ordering::Relaxed) {
+            let timeout = step_editor(&mut editor, &event_rx).or(poll);
+
+            if editor.render {
+                continue;
+            }
+
+            let event = match timeout {
+                Some(timeout) => event_rx.recv_timeout(timeout).ok(),
+                None => event_rx.recv().ok(),
+            };
+
+            if let Some(event) = event {
+                handle_event(&mut editor, event);
+                handle_app_requests(&mut editor);
+            }
+        }
+
+        // let the wake thread see that the editor quit
+        redraw.wake();
+    })
+}
+
+/// Spawn a thread waking the editor thread when a redraw is requested, so the audio thread
+/// doesn't need to send events.
+///
+/// Returns `None` if the eventfd couldn't be created.
+fn spawn_wake_thread(
+    redraw: Redraw,
+    event_tx: Sender<EditorEvent>,
+    running: Arc<AtomicBool>,
+) -> Option<JoinHandle<()>> {
+    if !redraw.init_wake() {
+        warn!("Could not create the eventfd waking the editor, polling for redraws");
+        return None;
+    }
+
+    let thread = thread::spawn(move || {
+        while redraw.wait() {
+            if !running.load(Ordering::Relaxed) {
+                // the wake may have been meant for the next editor
+                redraw.wake();
+                break;
+            }
+
+            if event_tx.send(EditorEvent::Wake).is_err() {
+                break;
+            }
+        }
+    });
+
+    Some(thread)
+}
+
+/// Create the editor on `display`, as a child of `parent` or of the root window if null.
+unsafe fn create_editor<P: VstPlugin>(
+    display: *mut Display,
+    parent: *mut ffi::c_void,
+    state: Arc<PluginState<P>>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+    handle: Arc<X11EditorHandle>,
+    window: Window,
+    ui: EditorUi<P>,
+) -> X11Editor<P> {
+    let parent = match parent.is_null() {
+        true => (XLIB.XDefaultRootWindow)(display) as *mut ffi::c_void,
+        false => parent,
+    };
+
+    let app = App::build().window(window, ui);
+
+    let waker = CommandWaker::new({
+        let event_tx = handle.event_tx.clone();
+
+        move || {
+            if let Err(err) = event_tx.send(EditorEvent::Wake) {
+                warn!("Error sending wake event: {:?}", err);
+            }
+        }
+    });
+
+    let fonts = Box::new(handle.options.create_fonts());
+
+    let mut app = app.build(waker, fonts);
+    app.contexts.insert(state.host.clone());
+
+    let selections = Arc::new(Mutex::new(Selections::new(display)));
+    let clipboard = X11Clipboard::new(selections.clone());
+    app.contexts.insert(Clipboard::new(Box::new(clipboard)));
+
+    let params = state.param_values();
+
+    let options = P::editor();
+    let frame_interval = frame_interval(display, options.frame_rate);
+
+    if options.animate {
+        state.host.redraw().start_animation();
+    }
+
+    let scale = handle.scale();
+
+    let mut editor = X11Editor {
+        parent,
+        display,
+        event_thread: None,
+        handle,
+        state,
+        frame,
+
+        params,
+
+        app,
+        windows: Vec::new(),
+        wm_protocols: (XLIB.XInternAtom)(display, c"WM_PROTOCOLS".as_ptr(), 0),
+        wm_delete_window: (XLIB.XInternAtom)(display, c"WM_DELETE_WINDOW".as_ptr(), 0),
+        im: InputMethod::open(display),
+        selections,
+        dnd: Dnd::new(display),
+
+        render: true,
+        running: Arc::new(AtomicBool::new(true)),
+        scale,
+
+        frame_interval,
+        next_frame: Instant::now(),
+    };
+
+    editor.app.init(&mut editor.state.plugin.lock());
+
+    editor
+}
+
+/// Run one iteration of the editor, returns how long until the next frame while animating.
+unsafe fn step_editor<P: VstPlugin>(
+    editor: &mut X11Editor<P>,
+    event_rx: &Receiver<EditorEvent>,
+) -> Option<Duration> {
+    (XLIB.XFlush)(editor.display);
+
+    editor.app.idle(&mut editor.state.plugin.lock());
+    handle_app_requests(editor);
+
+    render_windows(editor);
+    handle_app_requests(editor);
+
+    while let Ok(event) = event_rx.try_recv() {
+        handle_event(editor, event);
+        handle_app_requests(editor);
+    }
+
+    let mut edits = Vec::new();
+    editor.params = editor.state.take_editor_edits(&editor.params, &mut edits);
+
+    // only report the edits of the editor, reporting values set by the host would echo them
+    for i in edits {
+        let component = editor.state.component.lock();
+        if let Some(component) = component.as_ref() {
+            let _ = component.begin_edit(i as u32);
+
+            component.perform_edit(i as u32, editor.params[i] as f64);
+
+            let _ = component.end_edit(i as u32);
+
+            component.restart_component(RestartFlags::kParamValuesChanged as i32);
+        }
+    }
+
+    let redraw = editor.state.host.redraw();
+
+    if redraw.take() {
+        handle_event(editor, EditorEvent::Rebuild);
+        handle_app_requests(editor);
+
+        editor.render = true;
+    }
+
+    match redraw.is_animating() {
+        true => Some(next_frame(editor)),
+        false => None,
+    }
+}
+
+/// Advance the animation timer, returning the time until the next frame.
+fn next_frame<P: VstPlugin>(editor: &mut X11Editor<P>) -> Duration {
+    let now = Instant::now();
+
+    if now >= editor.next_frame {
+        editor.render = true;
+        editor.next_frame += editor.frame_interval;
+
+        // don't try to catch up on frames we missed
+        if editor.next_frame < now {
+            editor.next_frame = now + editor.frame_interval;
+        }
+    }
+
+    editor.next_frame.saturating_duration_since(now)
+}
+
+unsafe fn frame_interval(display: *mut Display, frame_rate: FrameRate) -> Duration {
+    let fps = match frame_rate {
+        FrameRate::Fps(fps) if !fps.is_nan() => fps,
+        FrameRate::Fps(_) => DEFAULT_FRAME_RATE,
+        FrameRate::Display => display_refresh_rate(display).unwrap_or(DEFAULT_FRAME_RATE),
+    };
+
+    Duration::from_secs_f32(1.0 / fps.clamp(1.0, MAX_FRAME_RATE))
+}
+
+unsafe fn display_refresh_rate(display: *mut Display) -> Option<f32> {
+    let xrandr = Xrandr::open().ok()?;
+
+    let root = (XLIB.XDefaultRootWindow)(display);
+    let config = (xrandr.XRRGetScreenInfo)(display, root);
+
+    if config.is_null() {
+        return None;
+    }
+
+    let rate = (xrandr.XRRConfigCurrentRate)(config);
+    (xrandr.XRRFreeScreenConfigInfo)(config);
+
+    match rate > 0 {
+        true => Some(rate as f32),
+        false => None,
+    }
+}
+
+unsafe fn handle_event<P: VstPlugin>(editor: &mut X11Editor<P>, event: EditorEvent) {
+    match event {
+        EditorEvent::Wake => {}
+        EditorEvent::XEvent(event) => handle_xevent(editor, event),
+        EditorEvent::Quit => editor.running.store(false, Ordering::Relaxed),
+        EditorEvent::Resize(width, height) => {
+            editor.handle.width.store(width, Ordering::Relaxed);
+            editor.handle.height.store(height, Ordering::Relaxed);
+
+            editor.render = true;
+
+            if let Some(window) = editor.windows.first_mut() {
+                window.width = width;
+                window.height = height;
+
+                (XLIB.XResizeWindow)(editor.display, window.window, width, height);
+
+                let width = logical(width, editor.scale);
+                let height = logical(height, editor.scale);
+
+                let mut plugin = editor.state.plugin.lock();
+                (editor.app).window_resized(&mut plugin, window.id, width, height);
+            }
+        }
+        EditorEvent::Scale(scale) => set_scale(editor, scale),
+        EditorEvent::HostKey {
+            key,
+            key_code,
+            modifiers,
+            pressed,
+        } => {
+            let handled = handle_host_key(editor, key, key_code, modifiers, pressed);
+            editor.handle.key_handled.store(handled, Ordering::Relaxed);
+        }
+        EditorEvent::HostWheel { distance } => {
+            let handled = handle_host_wheel(editor, distance);
+            editor
+                .handle
+                .wheel_handled
+                .store(handled, Ordering::Relaxed);
+        }
+        EditorEvent::Rebuild => {
+            let mut plugin = editor.state.plugin.lock();
+            (editor.app).rebuild(&mut plugin);
+        }
+    }
+}
+
+unsafe fn handle_xevent<P: VstPlugin>(editor: &mut X11Editor<P>, mut event: XEvent) {
+    // the input method consumes the key events of dead keys and compositions
+    if ime::filter_event(&mut event) {
+        return;
+    }
+
+    if editor.dnd.is_selection(&event) {
+        let drop = editor
+            .dnd
+            .handle_selection(editor.display, &event.selection);
+        send_file_drop(editor, drop);
+
+        return;
+    }
+
+    if editor.selections.lock().handle_event(&event) {
+        return;
+    }
+
+    let Some(index) = editor
+        .windows
+        .iter()
+        .position(|w| w.window == event.any.window)
+    else {
+        return;
+    };
+
+    let id = editor.windows[index].id;
+
+    match event.type_ {
+        xlib::ClientMessage => {
+            // the window manager asks top-level windows to close
+            let message = &event.client_message;
+
+            if message.message_type == editor.wm_protocols
+                && message.data.get_long(0) as xlib::Atom == editor.wm_delete_window
+            {
+                let mut plugin = editor.state.plugin.lock();
+                editor.app.close_requested(&mut plugin, id);
+
+                return;
+            }
+
+            let window = editor.windows[index].window;
+            let drop = (editor.dnd).handle_client_message(
+                editor.display,
+                window,
+                &event.client_message,
+                editor.scale,
+            );
+
+            send_file_drop(editor, drop);
+        }
+        xlib::FocusIn | xlib::FocusOut => {
+            if let Some(ref ic) = editor.windows[index].ic {
+                ic.set_focus(event.type_ == xlib::FocusIn);
+            }
+
+            if event.type_ == xlib::FocusIn {
+                editor.selections.lock().request();
+            }
+        }
+        xlib::Expose => {
+            editor.render = true;
+        }
+        xlib::ConfigureNotify => {
+            let width = event.configure.width as u32;
+            let height = event.configure.height as u32;
+
+            let window = &mut editor.windows[index];
+            let resized = (window.width, window.height) != (width, height);
+
+            window.width = width;
+            window.height = height;
+
+            if index == 0 {
+                editor.handle.width.store(width, Ordering::Relaxed);
+                editor.handle.height.store(height, Ordering::Relaxed);
+            } else if resized {
+                // secondary windows are resized by the window manager
+                let width = logical(width, editor.scale);
+                let height = logical(height, editor.scale);
+
+                let mut plugin = editor.state.plugin.lock();
+                (editor.app).window_resized(&mut plugin, id, width, height);
+            }
+
+            editor.render = true;
+        }
+        xlib::MotionNotify => {
+            let position = Point::new(event.motion.x as f32, event.motion.y as f32);
+            let position = position / editor.scale;
+
+            let mut plugin = editor.state.plugin.lock();
+            (editor.app).pointer_moved(&mut plugin, id, PointerId::from_u64(0), position);
+        }
+        xlib::EnterNotify => {
+            let cursor = editor.windows[index].cursor;
+            set_window_cursor(editor, id, cursor);
+        }
+        xlib::LeaveNotify => {
+            let mut plugin = editor.state.plugin.lock();
+            (editor.app).pointer_left(&mut plugin, id, PointerId::from_u64(0));
+        }
+        xlib::ButtonPress => {
+            handle_pointer_button(
+                editor,
+                id,
+                PointerId::from_u64(0),
+                event.button.button,
+                true,
+            );
+        }
+        xlib::ButtonRelease => {
+            handle_pointer_button(
+                editor,
+                id,
+                PointerId::from_u64(0),
+                event.button.button,
+                false,
+            );
+        }
+        xlib::KeyPress => {
+            // fetch the clipboard ahead of a paste, converting a selection is asynchronous
+            let keysym = Keysym::new((XLIB.XLookupKeysym)(&mut event.key, 0) as u32);
+            if keysym == Keysym::Control_L || keysym == Keysym::Control_R {
+                editor.selections.lock().request();
+            }
+
+            let modifiers = Modifiers {
+                shift: event.key.state & xlib::ShiftMask != 0,
+                ctrl: event.key.state & xlib::ControlMask != 0,
+                alt: event.key.state & xlib::Mod1Mask != 0,
+                meta: event.key.state & xlib::Mod4Mask != 0,
+            };
+
+            editor.app.modifiers_changed(modifiers);
+
+            let text = match editor.windows[index].ic {
+                Some(ref ic) => ic.lookup(&mut event.key),
+                None => get_key_text(&mut event.key).0,
+            };
+
+            event.key.state = 0;
+            let (_, key) = get_key_text(&mut event.key);
+
+            // committed IME text arrives as key presses without a keycode
+            let scancode = event.key.keycode as u8;
+            let keycode = scancode.checked_sub(8).and_then(Code::from_linux_scancode);
+
+            let mut plugin = editor.state.plugin.lock();
+
+            (editor.app).keyboard_key(&mut plugin, id, key, keycode, text, true);
+        }
+        xlib::KeyRelease => {
+            let modifiers = Modifiers {
+                shift: event.key.state & xlib::ShiftMask != 0,
+                ctrl: event.key.state & xlib::ControlMask != 0,
+                alt: event.key.state & xlib::Mod1Mask != 0,
+                meta: event.key.state & xlib::Mod4Mask != 0,
+            };
+
+            editor.app.modifiers_changed(modifiers);
+
+            event.key.state = 0;
+            let (_, key) = get_key_text(&mut event.key);
+
+            let scancode = event.key.keycode as u8;
+            let keycode = Code::from_linux_scancode(scancode - 8);
+
+            let mut plugin = editor.state.plugin.lock();
+
+            (editor.app).keyboard_key(&mut plugin, id, key, keycode, None, false);
+        }
+        _ => {}
+    }
+}
+
+/// Send a file drop to the views of the editor.
+fn send_file_drop<P: VstPlugin>(editor: &mut X11Editor<P>, drop: Option<FileDrop>) {
+    if let Some(drop) = drop {
+        editor.app.proxy().cmd(drop);
+    }
+}
+
+/// Handle a key forwarded by the host through `IPlugView::on_key_down` and `on_key_up`.
+unsafe fn handle_host_key<P: VstPlugin>(
+    editor: &mut X11Editor<P>,
+    key: u16,
+    key_code: i16,
+    modifiers: i16,
+    pressed: bool,
+) -> bool {
+    let Some(window) = editor.main_window() else {
+        return false;
+    };
+
+    let id = window.id;
+
+    let modifiers = Modifiers {
+        shift: modifiers & K_SHIFT_KEY != 0,
+        ctrl: modifiers & K_COMMAND_KEY != 0,
+        alt: modifiers & K_ALTERNATE_KEY != 0,
+        meta: modifiers & K_CONTROL_KEY != 0,
+    };
+
+    let text = char::from_u32(key as u32).filter(|c| *c != '\0' && !c.is_control());
+
+    let key = match virtual_key_to_keysym(key_code) {
+        Some(keysym) => keysym_to_key(keysym),
+        None => match text {
+            Some(c) => Key::Character(c),
+            None => return false,
+        },
+    };
+
+    let text = text.filter(|_| pressed).map(String::from);
+
+    editor.app.modifiers_changed(modifiers);
+
+    let mut plugin = editor.state.plugin.lock();
+    (editor.app).keyboard_key(&mut plugin, id, key, None, text, pressed)
+}
+
+/// Handle a mouse wheel forwarded by the host through `IPlugView::on_wheel`.
+unsafe fn handle_host_wheel<P: VstPlugin>(editor: &mut X11Editor<P>, distance: f32) -> bool {
+    let Some(window) = editor.main_window() else {
+        return false;
+    };
+
+    let id = window.id;
+
+    let mut plugin = editor.state.plugin.lock();
+    let pointer = PointerId::from_u64(0);
+
+    (editor.app).pointer_scrolled(&mut plugin, id, pointer, Vector::Y * distance)
+}
+
+const K_SHIFT_KEY: i16 = 1 << 0;
+const K_ALTERNATE_KEY: i16 = 1 << 1;
+const K_COMMAND_KEY: i16 = 1 << 2;
+const K_CONTROL_KEY: i16 = 1 << 3;
+
+/// Map a VST3 `VirtualKeyCodes` value to a keysym.
+fn virtual_key_to_keysym(key_code: i16) -> Option<Keysym> {
+    let keysym = match key_code {
+        1 => Keysym::BackSpace,
+        2 => Keysym::Tab,
+        3 => Keysym::Clear,
+        4 => Keysym::Return,
+        5 => Keysym::Pause,
+        6 => Keysym::Escape,
+        7 => Keysym::space,
+        8 => Keysym::Next,
+        9 => Keysym::End,
+        10 => Keysym::Home,
+        11 => Keysym::Left,
+        12 => Keysym::Up,
+        13 => Keysym::Right,
+        14 => Keysym::Down,
+        15 => Keysym::Page_Up,
+        16 => Keysym::Page_Down,
+        17 => Keysym::Select,
+        18 => Keysym::Print,
+        19 => Keysym::KP_Enter,
+        20 => Keysym::Print,
+        21 => Keysym::Insert,
+        22 => Keysym::Delete,
+        23 => Keysym::Help,
+        24 => Keysym::KP_0,
+        25 => Keysym::KP_1,
+        26 => Keysym::KP_2,
+        27 => Keysym::KP_3,
+        28 => Keysym::KP_4,
+        29 => Keysym::KP_5,
+        30 => Keysym::KP_6,
+        31 => Keysym::KP_7,
+        32 => Keysym::KP_8,
+        33 => Keysym::KP_9,
+        34 => Keysym::KP_Multiply,
+        35 => Keysym::KP_Add,
+        36 => Keysym::KP_Separator,
+        37 => Keysym::KP_Subtract,
+        38 => Keysym::KP_Decimal,
+        39 => Keysym::KP_Divide,
+        40..=63 => Keysym::new(Keysym::F1.raw() + (key_code - 40) as u32),
+        64 => Keysym::Num_Lock,
+        65 => Keysym::Scroll_Lock,
+        66 => Keysym::Shift_L,
+        67 => Keysym::Control_L,
+        68 => Keysym::Alt_L,
+        69 => Keysym::KP_Equal,
+        70 => Keysym::Menu,
+        _ => return None,
+    };
+
+    Some(keysym)
+}
+
+/// Look up the text and key of a key event without an input method.
+unsafe fn get_key_text(key: &mut XKeyEvent) -> (Option<String>, Key) {
+    let mut text = [0u8; 64];
+    let mut keysym = 0;
+
+    let count = (XLIB.XLookupString)(
+        key,
+        text.as_mut_ptr() as *mut ffi::c_char,
+        text.len() as i32,
+        &mut keysym,
+        ptr::null_mut(),
+    );
+
+    let text = match count {
+        0 => None,
+        count => {
+            // `XLookupString` returns Latin-1, which maps directly to the first 256 code points
+            let text = &text[..usize::min(count as usize, text.len())];
+            Some(text.iter().map(|&byte| byte as char).collect())
+        }
+    };
+
+    let keysym = Keysym::new(keysym as u32);
+
+    let mut chars = text.iter().flat_map(|text| text.chars());
+
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Key::Character(c),
+        _ => keysym_to_key(keysym),
+    };
+
+    (text, key)
+}
+
+unsafe fn handle_pointer_button<P: VstPlugin>(
+    editor: &mut X11Editor<P>,
+    window_id: WindowId,
+    pointer_id: PointerId,
+    button: u32,
+    pressed: bool,
+) {
+    let mut plugin = editor.state.plugin.lock();
+
+    match button {
+        code @ 4..8 => {
+            let delta = match code {
+                4 => Vector::Y,
+                5 => Vector::NEG_Y,
+                6 => Vector::X,
+                7 => Vector::NEG_X,
+                _ => unreachable!(),
+            };
+
+            (editor.app).pointer_scrolled(&mut plugin, window_id, pointer_id, delta);
+        }
+        _ => {
+            let button = PointerButton::from_u16(button as u16);
+
+            (editor.app).pointer_button(&mut plugin, window_id, pointer_id, button, pressed);
+        }
+    }
+}
+
+unsafe fn spawn_event_thread(
+    display: *mut Display,
+    event_tx: Sender<EditorEvent>,
+    running: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn({
+        let display = AssertSend(display);
+
+        move || {
+            let display = display;
+            let AssertSend(display) = display;
+
+            while running.load(Ordering::Relaxed) {
+                let mut event = MaybeUninit::uninit();
+
+                if (XLIB.XNextEvent)(display, event.as_mut_ptr()) != 0 {
+                    continue;
+                }
+
+                let event = event.assume_init();
+
+                if event_tx.send(EditorEvent::XEvent(event)).is_err() {
+                    break;
+                }
+            }
+        }
+    })
+}
+
+impl<P: VstPlugin> Drop for X11Editor<P> {
+    fn drop(&mut self) {
+        unsafe {
+            // the event thread reads from the display, so it must stop before the display closes
+            if let Some(event_thread) = self.event_thread.take() {
+                let Some(window) = self.main_window() else {
+                    warn!("Could not stop the event thread, leaking the display");
+                    return;
+                };
+
+                self.running.store(false, Ordering::Relaxed);
+                wake_event_thread(self.display, window.window);
+
+                let _ = event_thread.join();
+            }
+
+            // secondary windows share the GL resources of the main window, so they go first
+            while let Some(mut window) = self.windows.pop() {
+                destroy_window(self.display, &mut window);
+            }
+
+            self.im.take();
+            (XLIB.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+/// Send an event to `window`, waking the event thread waiting in `XNextEvent`.
+unsafe fn wake_event_thread(display: *mut Display, window: xlib::Window) {
+    let mut event = MaybeUninit::<XEvent>::zeroed().assume_init();
+
+    event.client_message.type_ = xlib::ClientMessage;
+    event.client_message.window = window;
+    event.client_message.format = 32;
+
+    // without an event mask, the event goes to the client that created the window
+    (XLIB.XSendEvent)(display, window, 0, 0, &mut event);
+    (XLIB.XFlush)(display);
+}
+
+enum EditorEvent {
+    Wake,
+    XEvent(XEvent),
+    Quit,
+    Resize(u32, u32),
+    Scale(f32),
+    Rebuild,
+    HostKey {
+        key: u16,
+        key_code: i16,
+        modifiers: i16,
+        pressed: bool,
+    },
+    HostWheel {
+        distance: f32,
+    },
+}
+
+struct AssertSend<T>(T);
+
+unsafe impl Send for EditorEvent {}
+unsafe impl<T> Send for AssertSend<T> {}
+
+pub fn keysym_to_key(keysym: Keysym) -> Key {
+    match keysym {
+        /* modifier keys */
+        Keysym::Alt_L | Keysym::Alt_R => Key::Alt,
+        Keysym::SUN_AltGraph | Keysym::ISO_Level3_Shift => Key::AltGraph,
+        Keysym::Control_L | Keysym::Control_R => Key::Control,
+        Keysym::Shift_L | Keysym::Shift_R => Key::Shift,
+        Keysym::Meta_L | Keysym::Meta_R => Key::Meta,
+        Keysym::Super_L | Keysym::Super_R => Key::Super,
+        Keysym::Hyper_L | Keysym::Hyper_R => Key::Hyper,
+        Keysym::XF86_Fn | Keysym::XF86_Launch1 => Key::Fn,
+
+        /* lock keys */
+        Keysym::Num_Lock => Key::NumLock,
+        Keysym::Caps_Lock => Key::CapsLock,
+        Keysym::Scroll_Lock => Key::ScrollLock,
+
+        /* function keys */
+        Keysym::F1 => Key::F1,
+        Keysym::F2 => Key::F2,
+        Keysym::F3 => Key::F3,
+        Keysym::F4 => Key::F4,
+        Keysym::F5 => Key::F5,
+        Keysym::F6 => Key::F6,
+        Keysym::F7 => Key::F7,
+        Keysym::F8 => Key::F8,
+        Keysym::F9 => Key::F9,
+        Keysym::F10 => Key::F10,
+        Keysym::F11 => Key::F11,
+        Keysym::F12 => Key::F12,
+        Keysym::F13 => Key::F13,
+        Keysym::F14 => Key::F14,
+        Keysym::F15 => Key::F15,
+        Keysym::F16 => Key::F16,
+        Keysym::F17 => Key::F17,
+        Keysym::F18 => Key::F18,
+        Keysym::F19 => Key::F19,
+        Keysym::F20 => Key::F20,
+        Keysym::F21 => Key::F21,
+        Keysym::F22 => Key::F22,
+        Keysym::F23 => Key::F23,
+        Keysym::F24 => Key::F24,
+
+        /* character keys without text, like keys forwarded by the host */
+        Keysym::space | Keysym::KP_Space => Key::Character(' '),
+        Keysym::KP_0 => Key::Character('0'),
+        Keysym::KP_1 => Key::Character('1'),
+        Keysym::KP_2 => Key::Character('2'),
+        Keysym::KP_3 => Key::Character('3'),
+        Keysym::KP_4 => Key::Character('4'),
+        Keysym::KP_5 => Key::Character('5'),
+        Keysym::KP_6 => Key::Character('6'),
+        Keysym::KP_7 => Key::Character('7'),
+        Keysym::KP_8 => Key::Character('8'),
+        Keysym::KP_9 => Key::Character('9'),
+        Keysym::KP_Multiply => Key::Character('*'),
+        Keysym::KP_Add => Key::Character('+'),
+        Keysym::KP_Separator => Key::Character(','),
+        Keysym::KP_Subtract => Key::Character('-'),
+        Keysym::KP_Decimal => Key::Character('.'),
+        Keysym::KP_Divide => Key::Character('/'),
+        Keysym::KP_Equal => Key::Character('='),
+
+        /* misc keys */
+        Keysym::Return | Keysym::KP_Enter => Key::Enter,
+        Keysym::Tab => Key::Tab,
+        Keysym::Down => Key::Down,
+        Keysym::Left => Key::Left,
+        Keysym::Right => Key::Right,
+        Keysym::Up => Key::Up,
+        Keysym::End => Key::End,
+        Keysym::Home => Key::Home,
+        Keysym::Page_Down => Key::PageDown,
+        Keysym::Page_Up => Key::PageUp,
+        Keysym::BackSpace => Key::Backspace,
+        Keysym::Clear => Key::Clear,
+        Keysym::SUN_Copy | Keysym::XF86_Copy => Key::Copy,
+        Keysym::SUN_Cut | Keysym::XF86_Cut => Key::Cut,
+        Keysym::Delete => Key::Delete,
+        Keysym::Insert => Key::Insert,
+        Keysym::SUN_Paste | Keysym::OSF_Paste | Keysym::XF86_Paste => Key::Paste,
+        Keysym::Cancel => Key::Cancel,
+        Keysym::Escape => Key::Escape,
+        Keysym::Execute => Key::Execute,
+        Keysym::Find => Key::Find,
+        Keysym::Help => Key::Help,
+        Keysym::Pause => Key::Pause,
+        Keysym::Select => Key::Select,
+        Keysym::SUN_Print_Screen => Key::PrintScreen,
+        Keysym::Codeinput => Key::CodeInput,
+
+        _ => Key::Unidentified,
+    }
+}
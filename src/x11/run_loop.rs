@@ -0,0 +1,285 @@
+use std::{
+    ffi::{c_int, c_void},
+    mem::{self, MaybeUninit},
+    ptr::{self, NonNull},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+        Arc,
+    },
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use vst3_com::{com_interface, interfaces::IUnknown, VstPtr};
+use vst3_sys::{
+    base::{kResultOk, tresult},
+    utils::SharedVstPtr,
+    VST3,
+};
+
+use x11_dl::xlib::Display;
+
+use super::{
+    handle_app_requests, handle_event, step_editor, EditorEvent, X11Editor, X11EditorHandle, XLIB,
+};
+use crate::{editor::EditorHandle, PlugFrame, VstPlugin};
+
+pub type FileDescriptor = c_int;
+pub type TimerInterval = u64;
+
+/// `Steinberg::Linux::IEventHandler`, called when a registered file descriptor is readable.
+#[com_interface("561E65C9-13A0-496F-813A-2C35654D7983")]
+pub trait IEventHandler: IUnknown {
+    unsafe fn on_fd_is_set(&self, fd: FileDescriptor);
+}
+
+/// `Steinberg::Linux::ITimerHandler`, called periodically by the host.
+#[com_interface("10BDD94F-4142-4774-821F-AD8FECA72CA9")]
+pub trait ITimerHandler: IUnknown {
+    unsafe fn on_timer(&self);
+}
+
+/// `Steinberg::Linux::IRunLoop`, the event loop of the host's UI thread.
+#[com_interface("18C35366-9776-4F1A-9C5B-83857A871389")]
+pub trait IRunLoop: IUnknown {
+    unsafe fn register_event_handler(
+        &self,
+        handler: SharedVstPtr<dyn IEventHandler>,
+        fd: FileDescriptor,
+    ) -> tresult;
+
+    unsafe fn unregister_event_handler(&self, handler: SharedVstPtr<dyn IEventHandler>) -> tresult;
+
+    unsafe fn register_timer(
+        &self,
+        handler: SharedVstPtr<dyn ITimerHandler>,
+        milliseconds: TimerInterval,
+    ) -> tresult;
+
+    unsafe fn unregister_timer(&self, handler: SharedVstPtr<dyn ITimerHandler>) -> tresult;
+}
+
+/// Handlers registered with the host's run loop, waiting for the editor.
+pub(super) struct Registration<P: VstPlugin> {
+    handler: *mut RunLoopHandler<P>,
+    timer: VstPtr<dyn ITimerHandler>,
+}
+
+/// Register handlers for `display` with the host's run loop.
+///
+/// Returns `None` if the host refuses the handlers.
+pub(super) unsafe fn register<P: VstPlugin>(
+    run_loop: VstPtr<dyn IRunLoop>,
+    display: *mut Display,
+    interval: Duration,
+) -> Option<Registration<P>> {
+    let handler = RunLoopHandler::<P>::allocate(
+        Mutex::new(None),
+        Mutex::new(None),
+        run_loop.clone(),
+        Mutex::new(ptr::null_mut()),
+        AtomicBool::new(false),
+    );
+
+    let raw = Box::into_raw(handler);
+    let event_handler = VstPtr::<dyn IEventHandler>::owned(NonNull::new(raw)?.cast());
+    let timer_handler = event_handler.cast::<dyn ITimerHandler>()?;
+
+    *(*raw).timer.lock() = timer_handler.as_raw() as *mut c_void;
+
+    let fd = (XLIB.XConnectionNumber)(display);
+    let milliseconds = interval.as_millis().max(1) as TimerInterval;
+
+    let event_shared = mem::transmute(event_handler.as_raw());
+    if run_loop.register_event_handler(event_shared, fd) != kResultOk {
+        return None;
+    }
+
+    let timer_shared = mem::transmute(timer_handler.as_raw());
+    if run_loop.register_timer(timer_shared, milliseconds) != kResultOk {
+        run_loop.unregister_event_handler(mem::transmute(event_handler.as_raw()));
+        return None;
+    }
+
+    Some(Registration {
+        handler: raw,
+        timer: timer_handler,
+    })
+}
+
+impl<P: VstPlugin> Registration<P> {
+    /// Hand the editor to the run loop.
+    pub(super) unsafe fn attach(self, editor: X11Editor<P>, event_rx: Receiver<EditorEvent>) {
+        let handle = editor.handle.clone();
+
+        *(*self.handler).event_rx.lock() = Some(event_rx);
+        *(*self.handler).editor.lock() = Some(editor);
+
+        // calls from the host are handled right away, as the editor shares the host's thread
+        let pump = Pump(self.timer);
+        let _ = handle.pump.set(Box::new(move || pump.pump()));
+    }
+}
+
+struct Pump(VstPtr<dyn ITimerHandler>);
+
+unsafe impl Send for Pump {}
+unsafe impl Sync for Pump {}
+
+impl Pump {
+    fn pump(&self) {
+        unsafe { self.0.on_timer() };
+    }
+}
+
+/// Drives the editor from the host's run loop.
+///
+/// The editor is locked with `try_lock`, so calls made while it's busy, for example when the
+/// host calls back into the view during a resize, are handled by the outer call before it
+/// returns to the host.
+#[VST3(implements(IEventHandler, ITimerHandler))]
+pub(super) struct RunLoopHandler<P: VstPlugin> {
+    editor: Mutex<Option<X11Editor<P>>>,
+    event_rx: Mutex<Option<Receiver<EditorEvent>>>,
+    run_loop: VstPtr<dyn IRunLoop>,
+    timer: Mutex<*mut c_void>,
+
+    /// Set when the handler is called while the editor is busy.
+    reentered: AtomicBool,
+}
+
+impl<P: VstPlugin> RunLoopHandler<P> {
+    unsafe fn update(&self) {
+        let Some(mut editor) = self.editor.try_lock() else {
+            self.reentered.store(true, Ordering::Relaxed);
+            return;
+        };
+
+        let Some(ref mut inner) = *editor else {
+            return;
+        };
+
+        let Some(ref event_rx) = *self.event_rx.lock() else {
+            return;
+        };
+
+        step_editor(inner, event_rx);
+
+        // handle the events sent by calls from the host while the editor was busy, a quit from
+        // `IPlugView::removed` must be handled before the host destroys the parent window
+        while self.reentered.swap(false, Ordering::Relaxed) {
+            while let Ok(event) = event_rx.try_recv() {
+                handle_event(inner, event);
+                handle_app_requests(inner);
+            }
+        }
+
+        (XLIB.XFlush)(inner.display);
+
+        if inner.running.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // the event handler is the first interface of `Self`
+        let event_handler = self as *const Self as *mut c_void;
+        let timer = *self.timer.lock();
+
+        // unregister before the editor closes the display, and with it the file descriptor
+        self.run_loop
+            .unregister_event_handler(mem::transmute(event_handler));
+        self.run_loop.unregister_timer(mem::transmute(timer));
+
+        editor.take();
+    }
+}
+
+impl<P: VstPlugin> IEventHandler for RunLoopHandler<P> {
+    unsafe fn on_fd_is_set(&self, _fd: FileDescriptor) {
+        // re-entrant calls are noted by `update`
+        if let Some(mut editor) = self.editor.try_lock() {
+            if let Some(ref mut editor) = *editor {
+                while (XLIB.XPending)(editor.display) > 0 {
+                    let mut event = MaybeUninit::uninit();
+                    (XLIB.XNextEvent)(editor.display, event.as_mut_ptr());
+
+                    handle_event(editor, EditorEvent::XEvent(event.assume_init()));
+                    handle_app_requests(editor);
+                }
+            }
+        }
+
+        self.update();
+    }
+}
+
+impl<P: VstPlugin> ITimerHandler for RunLoopHandler<P> {
+    unsafe fn on_timer(&self) {
+        self.update();
+    }
+}
+
+/// A timer registered with the host's run loop, forwarding resizes from the editor thread.
+pub(super) struct ResizeTimer {
+    run_loop: VstPtr<dyn IRunLoop>,
+    timer: VstPtr<dyn ITimerHandler>,
+}
+
+unsafe impl Send for ResizeTimer {}
+
+/// Register a timer resizing the view through `frame` on the host's UI thread.
+///
+/// Returns `None` if the host refuses the timer.
+pub(super) unsafe fn register_resize(
+    run_loop: VstPtr<dyn IRunLoop>,
+    handle: Arc<X11EditorHandle>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+    interval: Duration,
+) -> Option<ResizeTimer> {
+    let handler = ResizeHandler::allocate(handle, frame);
+    let timer = VstPtr::<dyn ITimerHandler>::owned(NonNull::new(Box::into_raw(handler))?.cast());
+
+    let milliseconds = interval.as_millis().max(1) as TimerInterval;
+    let shared = mem::transmute(timer.as_raw());
+
+    if run_loop.register_timer(shared, milliseconds) != kResultOk {
+        return None;
+    }
+
+    Some(ResizeTimer { run_loop, timer })
+}
+
+impl ResizeTimer {
+    /// Unregister the timer, must be called from the host's UI thread.
+    pub(super) unsafe fn unregister(self) {
+        let shared = mem::transmute(self.timer.as_raw());
+        self.run_loop.unregister_timer(shared);
+    }
+}
+
+/// Resizes the view requested by the editor thread, as `IPlugFrame` may only be used from the
+/// host's UI thread.
+#[VST3(implements(ITimerHandler))]
+struct ResizeHandler {
+    handle: Arc<X11EditorHandle>,
+    frame: Arc<Mutex<Option<PlugFrame>>>,
+}
+
+impl ITimerHandler for ResizeHandler {
+    unsafe fn on_timer(&self) {
+        let Some((width, height)) = self.handle.pending_resize.lock().take() else {
+            return;
+        };
+
+        // don't hold the lock while the host calls back into the view
+        let frame = self.frame.lock().clone();
+
+        if let Some(frame) = frame {
+            if frame.resize_view(width, height) {
+                return;
+            }
+        }
+
+        self.handle.resize(width, height);
+    }
+}
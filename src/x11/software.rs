@@ -0,0 +1,232 @@
+use std::{ffi::c_ulong, mem, ptr, slice};
+
+use ori::prelude::{warn, Canvas, Color};
+use ori_skia::SkiaFonts;
+use x11_dl::xlib::{self, Display, TrueColor, XImage, ZPixmap};
+
+use super::XLIB;
+use crate::raster::RasterSurface;
+
+/// Renders the editor on the CPU and copies the pixels to the window with `XPutImage`.
+///
+/// Used when GLX is unavailable, or when forced with
+/// [`EditorOptions::with_software_rendering`](crate::EditorOptions::with_software_rendering).
+pub struct SoftwareRenderer {
+    gc: xlib::GC,
+    visual: *mut xlib::Visual,
+    depth: i32,
+    format: PixelFormat,
+    surface: Option<RasterSurface>,
+
+    /// The pixels converted to the format of the visual, unless they're copied as is.
+    converted: Vec<u8>,
+}
+
+/// How the pixels of the surface, in BGRA order, are put in the window.
+enum PixelFormat {
+    /// 32 bits per pixel with 8 bit channels in BGRA order, the pixels are copied as is.
+    Bgra,
+
+    /// A true color visual with other masks or pixel sizes, the pixels are converted.
+    Convert {
+        bits_per_pixel: i32,
+        red: Channel,
+        green: Channel,
+        blue: Channel,
+    },
+
+    /// A visual with a color map, which isn't supported, nothing is rendered.
+    Unsupported,
+}
+
+impl PixelFormat {
+    unsafe fn new(display: *mut Display, visual: *mut xlib::Visual, depth: i32) -> Self {
+        let visual = &*visual;
+
+        let Some(bits_per_pixel) = bits_per_pixel(display, depth) else {
+            return Self::Unsupported;
+        };
+
+        let masks = [visual.red_mask, visual.green_mask, visual.blue_mask];
+
+        if visual.class != TrueColor || masks.contains(&0) {
+            return Self::Unsupported;
+        }
+
+        if bits_per_pixel == 32 && masks == [0xff0000, 0xff00, 0xff] {
+            return Self::Bgra;
+        }
+
+        match bits_per_pixel {
+            16 | 24 | 32 => Self::Convert {
+                bits_per_pixel,
+                red: Channel::new(visual.red_mask),
+                green: Channel::new(visual.green_mask),
+                blue: Channel::new(visual.blue_mask),
+            },
+            _ => Self::Unsupported,
+        }
+    }
+}
+
+/// A color channel of a true color visual.
+struct Channel {
+    shift: u32,
+    bits: u32,
+}
+
+impl Channel {
+    fn new(mask: c_ulong) -> Self {
+        let shift = mask.trailing_zeros();
+        let bits = (mask >> shift).trailing_ones().min(8);
+
+        Self { shift, bits }
+    }
+
+    fn encode(&self, value: u8) -> u32 {
+        ((value as u32) >> (8 - self.bits)) << self.shift
+    }
+}
+
+/// Get the bits per pixel of images with `depth` on the server.
+unsafe fn bits_per_pixel(display: *mut Display, depth: i32) -> Option<i32> {
+    let mut count = 0;
+    let formats = (XLIB.XListPixmapFormats)(display, &mut count);
+
+    if formats.is_null() {
+        return None;
+    }
+
+    let bits_per_pixel = slice::from_raw_parts(formats, count as usize)
+        .iter()
+        .find(|format| format.depth == depth)
+        .map(|format| format.bits_per_pixel);
+
+    (XLIB.XFree)(formats as *mut _);
+
+    bits_per_pixel
+}
+
+impl SoftwareRenderer {
+    pub unsafe fn new(
+        display: *mut Display,
+        window: xlib::Window,
+        visual: *mut xlib::Visual,
+        depth: i32,
+    ) -> Self {
+        let gc = (XLIB.XCreateGC)(display, window, 0, ptr::null_mut());
+        let format = PixelFormat::new(display, visual, depth);
+
+        if let PixelFormat::Unsupported = format {
+            warn!("The visual of the window isn't supported by the software renderer");
+        }
+
+        Self {
+            gc,
+            visual,
+            depth,
+            format,
+            surface: None,
+            converted: Vec::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn render(
+        &mut self,
+        display: *mut Display,
+        window: xlib::Window,
+        fonts: &mut SkiaFonts,
+        canvas: &Canvas,
+        color: Color,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) {
+        if let PixelFormat::Unsupported = self.format {
+            return;
+        }
+
+        let resized = match self.surface {
+            Some(ref surface) => surface.width() != width || surface.height() != height,
+            None => true,
+        };
+
+        if resized {
+            self.surface = RasterSurface::new(width, height);
+        }
+
+        let Some(ref mut surface) = self.surface else {
+            return;
+        };
+
+        surface.render(fonts, canvas, color, scale);
+
+        let pixels = surface.pixels();
+
+        let (data, bits_per_pixel, bytes_per_line) = match self.format {
+            PixelFormat::Bgra => (pixels, 32, width as usize * 4),
+            PixelFormat::Convert {
+                bits_per_pixel,
+                ref red,
+                ref green,
+                ref blue,
+            } => {
+                let bytes_per_pixel = bits_per_pixel as usize / 8;
+
+                // rows are padded to 32 bits
+                let bytes_per_line = (width as usize * bytes_per_pixel).next_multiple_of(4);
+                self.converted.resize(bytes_per_line * height as usize, 0);
+
+                let rows = pixels.chunks_exact(width as usize * 4);
+                let lines = self.converted.chunks_exact_mut(bytes_per_line);
+
+                for (row, line) in rows.zip(lines) {
+                    let pixels = row.chunks_exact(4);
+                    let values = line.chunks_exact_mut(bytes_per_pixel);
+
+                    for (pixel, value) in pixels.zip(values) {
+                        let pixel =
+                            blue.encode(pixel[0]) | green.encode(pixel[1]) | red.encode(pixel[2]);
+
+                        value.copy_from_slice(&pixel.to_le_bytes()[..bytes_per_pixel]);
+                    }
+                }
+
+                (&self.converted[..], bits_per_pixel, bytes_per_line)
+            }
+            PixelFormat::Unsupported => return,
+        };
+
+        // the image only borrows the pixels, so it's initialized in place instead of being
+        // created with `XCreateImage`, which would free them when destroyed
+        let mut image: XImage = mem::zeroed();
+        image.width = width as i32;
+        image.height = height as i32;
+        image.format = ZPixmap;
+        image.data = data.as_ptr() as *mut _;
+        image.byte_order = xlib::LSBFirst;
+        image.bitmap_unit = 32;
+        image.bitmap_bit_order = xlib::LSBFirst;
+        image.bitmap_pad = 32;
+        image.depth = self.depth;
+        image.bytes_per_line = bytes_per_line as i32;
+        image.bits_per_pixel = bits_per_pixel;
+        image.red_mask = (*self.visual).red_mask;
+        image.green_mask = (*self.visual).green_mask;
+        image.blue_mask = (*self.visual).blue_mask;
+
+        if (XLIB.XInitImage)(&mut image) == 0 {
+            return;
+        }
+
+        (XLIB.XPutImage)(
+            display, window, self.gc, &mut image, 0, 0, 0, 0, width, height,
+        );
+    }
+
+    pub unsafe fn destroy(&mut self, display: *mut Display) {
+        (XLIB.XFreeGC)(display, self.gc);
+        self.surface = None;
+    }
+}
@@ -0,0 +1,142 @@
+use std::thread;
+
+use ori_vst::{ring_buffer, triple_buffer};
+
+#[test]
+fn ring_buffer_empty() {
+    let (_, mut consumer) = ring_buffer::<u32>(4);
+
+    assert!(consumer.is_empty());
+    assert_eq!(consumer.len(), 0);
+    assert_eq!(consumer.pop(), None);
+}
+
+#[test]
+fn ring_buffer_full() {
+    let (mut producer, mut consumer) = ring_buffer(3);
+
+    assert_eq!(producer.push(1), Ok(()));
+    assert_eq!(producer.push(2), Ok(()));
+    assert_eq!(producer.push(3), Ok(()));
+    assert_eq!(producer.push(4), Err(4));
+    assert_eq!(consumer.len(), 3);
+
+    assert_eq!(consumer.pop(), Some(1));
+    assert_eq!(producer.push(4), Ok(()));
+    assert_eq!(consumer.drain().collect::<Vec<_>>(), [2, 3, 4]);
+}
+
+#[test]
+fn ring_buffer_zero_capacity() {
+    let (mut producer, mut consumer) = ring_buffer(0);
+
+    assert_eq!(producer.push(1), Err(1));
+    assert_eq!(consumer.pop(), None);
+}
+
+#[test]
+fn ring_buffer_wraps_around() {
+    let (mut producer, mut consumer) = ring_buffer(4);
+
+    for round in 0..10 {
+        assert_eq!(producer.push_slice(&[round, round + 1, round + 2]), 3);
+        assert_eq!(consumer.len(), 3);
+
+        assert_eq!(consumer.pop(), Some(round));
+        assert_eq!(consumer.pop(), Some(round + 1));
+        assert_eq!(consumer.pop(), Some(round + 2));
+        assert!(consumer.is_empty());
+    }
+}
+
+#[test]
+fn ring_buffer_push_slice_partial() {
+    let (mut producer, mut consumer) = ring_buffer(4);
+
+    assert_eq!(producer.push_slice(&[1, 2, 3, 4, 5, 6]), 4);
+    assert_eq!(consumer.drain().collect::<Vec<_>>(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn ring_buffer_two_threads() {
+    const COUNT: u64 = 100_000;
+
+    let (mut producer, mut consumer) = ring_buffer(64);
+
+    let writer = thread::spawn(move || {
+        for value in 0..COUNT {
+            while producer.push(value).is_err() {
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut expected = 0;
+
+    while expected < COUNT {
+        match consumer.pop() {
+            Some(value) => {
+                assert_eq!(value, expected);
+                expected += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+
+    writer.join().unwrap();
+    assert!(consumer.is_empty());
+}
+
+#[test]
+fn triple_buffer_reads_initial_value() {
+    let (_, mut output) = triple_buffer(7);
+
+    assert!(!output.updated());
+    assert_eq!(*output.read(), 7);
+}
+
+#[test]
+fn triple_buffer_reads_latest_value() {
+    let (mut input, mut output) = triple_buffer(0);
+
+    input.write(1);
+    input.write(2);
+    assert!(output.updated());
+    assert_eq!(*output.read(), 2);
+    assert!(!output.updated());
+
+    // reading again without a publish returns the same value
+    assert_eq!(*output.read(), 2);
+
+    *input.input_mut() = 3;
+    assert!(!output.updated());
+    input.publish();
+    assert_eq!(*output.read(), 3);
+}
+
+#[test]
+fn triple_buffer_two_threads() {
+    const COUNT: u64 = 100_000;
+
+    let (mut input, mut output) = triple_buffer([0u64; 16]);
+
+    let writer = thread::spawn(move || {
+        for value in 1..=COUNT {
+            input.write([value; 16]);
+        }
+    });
+
+    let mut last = 0;
+
+    while last < COUNT {
+        let value = *output.read();
+
+        // a torn read would mix values from different writes
+        assert!(value.iter().all(|&v| v == value[0]));
+        assert!(value[0] >= last);
+
+        last = value[0];
+    }
+
+    writer.join().unwrap();
+}
@@ -0,0 +1,47 @@
+/// Define a plugin passing audio through `$channels` channels, without parameters.
+macro_rules! test_plugin {
+    ($plugin:ident, $uuid:literal, $name:literal, $channels:literal) => {
+        struct $plugin;
+
+        impl VstPlugin for $plugin {
+            fn info() -> Info {
+                Info {
+                    uuid: uuid!($uuid),
+                    name: String::from($name),
+                    subcategories: vec![Subcategory::Fx, Subcategory::Dynamics],
+                    vendor: String::from("Test Vendor"),
+                    version: String::from("1.0.0"),
+                    url: String::from("https://example.com"),
+                    email: String::from("test@example.com"),
+                }
+            }
+
+            fn layout(_inputs: &[u32], _outputs: &[u32]) -> Option<AudioLayout> {
+                let layout = AudioLayout::new()
+                    .with_input(AudioPort::new($channels))
+                    .with_output(AudioPort::new($channels));
+
+                Some(layout)
+            }
+
+            fn new() -> Self {
+                Self
+            }
+
+            fn ui(&mut self) -> impl View<Self> + 'static {
+                text($name)
+            }
+
+            fn process(
+                &mut self,
+                _buffer: &mut Buffer<'_>,
+                _aux_buffers: &mut [Buffer<'_>],
+                _layout: BufferLayout,
+            ) -> Process {
+                Process::Done
+            }
+        }
+    };
+}
+
+pub(crate) use test_plugin;
@@ -0,0 +1,54 @@
+mod common;
+
+use std::{mem, ptr::NonNull};
+
+use ori_vst::{prelude::*, Factory, PluginClass};
+use vst3_com::{ComInterface, VstPtr, IID};
+use vst3_sys::{
+    base::{kResultFalse, kResultOk, IPluginFactory},
+    vst::{IComponent, IConnectionPoint},
+};
+
+common::test_plugin!(
+    TestPlugin,
+    "5a4b3c2d-1e0f-4a9b-8c7d-6e5f4a3b2c1d",
+    "Test",
+    2
+);
+
+unsafe fn create_connection_point() -> VstPtr<dyn IConnectionPoint> {
+    let factory = Factory::new(vec![PluginClass::new::<TestPlugin>()]);
+    let factory = Box::into_raw(factory) as *mut _;
+    let factory = VstPtr::<dyn IPluginFactory>::owned(NonNull::new(factory).unwrap().cast());
+
+    let cid = IID {
+        data: TestPlugin::info().uuid.to_bytes_le(),
+    };
+    let iid = <dyn IComponent as ComInterface>::IID;
+    let mut obj = std::ptr::null_mut();
+
+    assert_eq!(factory.create_instance(&cid, &iid, &mut obj), kResultOk);
+
+    let component = VstPtr::<dyn IComponent>::owned(NonNull::new(obj).unwrap().cast());
+    component.cast::<dyn IConnectionPoint>().unwrap()
+}
+
+#[test]
+fn connect_rejects_self() {
+    unsafe {
+        let point = create_connection_point();
+
+        assert_eq!(point.connect(mem::transmute(point.as_raw())), kResultFalse);
+    }
+}
+
+#[test]
+fn connect_accepts_other_instance() {
+    unsafe {
+        let point = create_connection_point();
+        let other = create_connection_point();
+
+        assert_eq!(point.connect(mem::transmute(other.as_raw())), kResultOk);
+        assert_eq!(point.disconnect(mem::transmute(other.as_raw())), kResultOk);
+    }
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use std::{
+    ffi::{c_void, CStr},
+    mem,
+    ptr::{self, NonNull},
+};
+
+use ori_vst::{create_factory, prelude::*, Factory, PluginClass, Uuid};
+use vst3_com::{ComInterface, VstPtr, IID};
+use vst3_sys::{
+    base::{kInvalidArgument, kResultOk, IPluginFactory, PClassInfo},
+    vst::{BusDirections, BusInfo, IComponent, MediaTypes},
+};
+
+common::test_plugin!(
+    MonoPlugin,
+    "3f2d8a4e-1b6c-4d7e-8f90-a1b2c3d4e5f6",
+    "Mono",
+    1
+);
+common::test_plugin!(
+    StereoPlugin,
+    "7c9e6b5a-4d3c-4b2a-9e8f-0a1b2c3d4e5f",
+    "Stereo",
+    2
+);
+common::test_plugin!(
+    CopyPlugin,
+    "3f2d8a4e-1b6c-4d7e-8f90-a1b2c3d4e5f6",
+    "Copy",
+    2
+);
+
+ori_vst::export!(MonoPlugin, StereoPlugin);
+
+extern "system" {
+    fn GetPluginFactory() -> *mut c_void;
+}
+
+unsafe fn owned_factory(factory: *mut c_void) -> VstPtr<dyn IPluginFactory> {
+    VstPtr::owned(NonNull::new(factory).unwrap().cast())
+}
+
+unsafe fn class_names(factory: &VstPtr<dyn IPluginFactory>) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for index in 0..factory.count_classes() {
+        let mut info = mem::zeroed::<PClassInfo>();
+        assert_eq!(factory.get_class_info(index, &mut info), kResultOk);
+
+        let name = CStr::from_ptr(info.name.as_ptr());
+        names.push(name.to_string_lossy().into_owned());
+    }
+
+    names
+}
+
+unsafe fn create_component(
+    factory: &VstPtr<dyn IPluginFactory>,
+    uuid: Uuid,
+) -> Option<VstPtr<dyn IComponent>> {
+    let cid = IID {
+        data: uuid.to_bytes_le(),
+    };
+    let iid = <dyn IComponent as ComInterface>::IID;
+    let mut obj = ptr::null_mut();
+
+    match factory.create_instance(&cid, &iid, &mut obj) {
+        kResultOk => Some(VstPtr::owned(NonNull::new(obj)?.cast())),
+        _ => None,
+    }
+}
+
+unsafe fn input_channels(component: &VstPtr<dyn IComponent>) -> i32 {
+    let mut info = mem::zeroed::<BusInfo>();
+    let result = component.get_bus_info(
+        MediaTypes::kAudio as i32,
+        BusDirections::kInput as i32,
+        0,
+        &mut info,
+    );
+
+    assert_eq!(result, kResultOk);
+    info.channel_count
+}
+
+#[test]
+fn factory_dispatches_by_class_id() {
+    unsafe {
+        let factory = Factory::new(vec![
+            PluginClass::new::<MonoPlugin>(),
+            PluginClass::new::<StereoPlugin>(),
+        ]);
+        let factory = owned_factory(Box::into_raw(factory).cast());
+
+        assert_eq!(class_names(&factory), ["Mono", "Stereo"]);
+
+        let mono = create_component(&factory, MonoPlugin::info().uuid).unwrap();
+        let stereo = create_component(&factory, StereoPlugin::info().uuid).unwrap();
+
+        assert_eq!(input_channels(&mono), 1);
+        assert_eq!(input_channels(&stereo), 2);
+    }
+}
+
+#[test]
+fn factory_rejects_unknown_class_id() {
+    unsafe {
+        let factory = Factory::new(vec![PluginClass::new::<MonoPlugin>()]);
+        let factory = owned_factory(Box::into_raw(factory).cast());
+
+        let cid = IID { data: [0; 16] };
+        let iid = <dyn IComponent as ComInterface>::IID;
+        let mut obj = ptr::null_mut();
+
+        assert_eq!(
+            factory.create_instance(&cid, &iid, &mut obj),
+            kInvalidArgument
+        );
+        assert!(obj.is_null());
+    }
+}
+
+#[test]
+fn factory_detects_duplicate_uuid() {
+    let unique = Factory::new(vec![
+        PluginClass::new::<MonoPlugin>(),
+        PluginClass::new::<StereoPlugin>(),
+    ]);
+    assert_eq!(unique.duplicate_uuid(), None);
+
+    let classes = vec![
+        PluginClass::new::<MonoPlugin>(),
+        PluginClass::new::<StereoPlugin>(),
+        PluginClass::new::<CopyPlugin>(),
+    ];
+
+    let duplicate = Factory::new(classes.clone());
+    assert_eq!(duplicate.duplicate_uuid(), Some(MonoPlugin::info().uuid));
+
+    assert!(create_factory(classes).is_null());
+}
+
+#[test]
+fn export_creates_factory_with_every_class() {
+    unsafe {
+        let factory = owned_factory(GetPluginFactory());
+
+        assert_eq!(class_names(&factory), ["Mono", "Stereo"]);
+        assert!(create_component(&factory, StereoPlugin::info().uuid).is_some());
+    }
+}
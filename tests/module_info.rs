@@ -0,0 +1,52 @@
+mod common;
+
+use std::ptr::NonNull;
+
+use ori_vst::{module_info, prelude::*, Factory, ModuleInfo, PluginClass};
+use vst3_com::VstPtr;
+use vst3_sys::base::IPluginFactory;
+
+common::test_plugin!(
+    TestPlugin,
+    "0b6e3c1a-5d4f-4e2a-9c8b-7a6d5e4f3c2b",
+    "Test",
+    2
+);
+
+fn factory_module_info() -> ModuleInfo {
+    let factory = Box::into_raw(Factory::new(vec![PluginClass::new::<TestPlugin>()]));
+
+    unsafe {
+        let factory = NonNull::new(factory).unwrap().cast();
+        let factory = VstPtr::<dyn IPluginFactory>::owned(factory);
+
+        ModuleInfo::from_factory(&factory, "Test", "1.2.3")
+    }
+}
+
+#[test]
+fn module_info_matches_factory() {
+    let module_info = module_info::<TestPlugin>("Test", "1.2.3");
+
+    assert_eq!(module_info, factory_module_info());
+}
+
+#[test]
+fn module_info_json_uses_factory_cid() {
+    let json = module_info::<TestPlugin>("Test", "1.2.3").to_json();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let cid = TestPlugin::info()
+        .uuid
+        .to_bytes_le()
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<String>();
+
+    let class = &json["Classes"][0];
+    assert_eq!(class["CID"], cid.as_str());
+    assert_eq!(
+        class["Sub Categories"],
+        serde_json::json!(["Fx", "Dynamics"])
+    );
+}
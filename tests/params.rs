@@ -0,0 +1,174 @@
+use ori_vst::prelude::*;
+
+#[derive(Params)]
+#[params(default)]
+struct FilterParams {
+    #[param(name = "Cutoff", unit = Unit::Frequency, range = 20.0..=20000.0, default = 1000.0)]
+    cutoff: Float,
+
+    #[param(id = "q", range = 0.5..=10.0)]
+    resonance: Float,
+}
+
+#[derive(Params)]
+#[params(default)]
+struct TestParams {
+    #[param(name = "Gain", short = "G", range = 0.0..=2.0, default = 1.0, automate)]
+    gain: Float,
+
+    #[param(default = true, bypass, automate)]
+    enabled: Bool,
+
+    #[param(hidden, read_only)]
+    meter: Float,
+
+    #[param(group)]
+    filter: FilterParams,
+}
+
+/// Without `#[params(default)]` the struct can implement `Default` itself.
+#[derive(Params)]
+struct CustomParams {
+    #[param(name = "Mix")]
+    mix: Float,
+}
+
+impl Default for CustomParams {
+    fn default() -> Self {
+        Self {
+            mix: Float::new(0.25, 0.0..=1.0),
+        }
+    }
+}
+
+#[test]
+fn derive_default_uses_range_and_default() {
+    let params = TestParams::default();
+
+    assert_eq!(params.gain.range, 0.0..=2.0);
+    assert_eq!(*params.gain, 1.0);
+    assert!(*params.enabled);
+
+    // without attributes the range is `0.0..=1.0` starting at its start
+    assert_eq!(params.meter.range, 0.0..=1.0);
+    assert_eq!(*params.meter, 0.0);
+
+    assert_eq!(*params.filter.cutoff, 1000.0);
+    assert_eq!(*params.filter.resonance, 0.5);
+}
+
+#[test]
+fn derive_info_names_and_flags() {
+    let params = TestParams::default();
+
+    assert_eq!(params.count(), 5);
+
+    let gain = params.info(0).unwrap();
+    assert_eq!(gain.name, "Gain");
+    assert_eq!(gain.short, "G");
+    assert_eq!(gain.flags.bits(), ParamFlags::AUTOMATE.bits());
+    assert_eq!(gain.default_normalized, 0.5);
+
+    let enabled = params.info(1).unwrap();
+    assert_eq!(enabled.name, "enabled");
+    let flags = ParamFlags::BYPASS | ParamFlags::AUTOMATE;
+    assert_eq!(enabled.flags.bits(), flags.bits());
+
+    let meter = params.info(2).unwrap();
+    let flags = ParamFlags::HIDDEN | ParamFlags::READ_ONLY;
+    assert_eq!(meter.flags.bits(), flags.bits());
+
+    let cutoff = params.info(3).unwrap();
+    assert_eq!(cutoff.name, "Cutoff");
+    assert_eq!(cutoff.unit, Unit::Frequency);
+
+    assert!(params.info(5).is_none());
+}
+
+#[test]
+fn derive_identifiers_of_groups() {
+    let params = TestParams::default();
+
+    let identifiers: Vec<_> = (0..params.count())
+        .map(|index| params.identifier(index).unwrap())
+        .collect();
+
+    assert_eq!(
+        identifiers,
+        ["gain", "enabled", "meter", "filter_cutoff", "filter_q"]
+    );
+}
+
+#[test]
+fn derive_param_indices_of_groups() {
+    let mut params = TestParams::default();
+
+    params.param(4).unwrap().set(2.0);
+    assert_eq!(*params.filter.resonance, 2.0);
+
+    assert!(params.param(5).is_none());
+}
+
+#[test]
+fn derive_keeps_custom_default() {
+    let mut params = CustomParams::default();
+
+    assert_eq!(*params.mix, 0.25);
+    assert_eq!(params.info(0).unwrap().name, "Mix");
+    assert_eq!(params.param(0).unwrap().get(), 0.25);
+}
+
+fn take_changed(params: &mut dyn Params) -> Vec<usize> {
+    let mut changed = Vec::new();
+    params.take_changed(&mut |index| changed.push(index));
+    changed
+}
+
+#[test]
+fn take_changed_offsets_groups() {
+    let mut params = TestParams::default();
+    assert!(take_changed(&mut params).is_empty());
+
+    params.param(0).unwrap().set(0.5);
+    params.param(3).unwrap().set(500.0);
+    params.param(4).unwrap().set(1.0);
+
+    assert_eq!(take_changed(&mut params), [0, 3, 4]);
+    assert!(take_changed(&mut params).is_empty());
+
+    // writing the value directly doesn't count as a change
+    *params.filter.cutoff = 200.0;
+    assert!(take_changed(&mut params).is_empty());
+}
+
+#[test]
+fn take_changed_offsets_vec() {
+    let mut params = vec![FilterParams::default(), FilterParams::default()];
+    assert_eq!(params.count(), 4);
+
+    params.param(1).unwrap().set(1.0);
+    params.param(2).unwrap().set(100.0);
+
+    assert_eq!(take_changed(&mut params), [1, 2]);
+    assert!(take_changed(&mut params).is_empty());
+}
+
+#[test]
+fn param_changes_only_through_set() {
+    let mut param = Bool::new(false).name("Enabled");
+    assert!(!param.take_changed());
+
+    // writing the value directly doesn't count as a change
+    *param = true;
+    assert!(!param.take_changed());
+
+    param.set(0.0);
+    assert!(param.take_changed());
+    assert!(!param.take_changed());
+}
+
+#[test]
+fn derive_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,91 @@
+#[path = "../examples/gain.rs"]
+mod gain;
+
+use ori_vst::{Snapshot, VstPlugin};
+
+/// A decoded snapshot.
+struct Image {
+    width: u32,
+    height: u32,
+    channels: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn decode(png: &[u8]) -> Self {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+
+        Self {
+            width: info.width,
+            height: info.height,
+            channels: info.color_type.samples(),
+            pixels,
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> &[u8] {
+        let start = (y * self.width + x) as usize * self.channels;
+        &self.pixels[start..start + self.channels]
+    }
+}
+
+#[test]
+fn snapshot_window_size() {
+    let png = Snapshot::new().render::<gain::GainPlugin>().unwrap();
+    let window = gain::GainPlugin::window();
+
+    let image = Image::decode(&png);
+    assert_eq!(image.size(), (window.width(), window.height()));
+}
+
+#[test]
+fn snapshot_scaled_size() {
+    let png = Snapshot::new()
+        .with_size(400, 300)
+        .with_scale(2.0)
+        .with_param("gain", 0.5)
+        .render::<gain::GainPlugin>()
+        .unwrap();
+
+    let image = Image::decode(&png);
+    assert_eq!(image.size(), (800, 600));
+}
+
+#[test]
+fn snapshot_draws_ui() {
+    let png = Snapshot::new().render::<gain::GainPlugin>().unwrap();
+    let image = Image::decode(&png);
+
+    // the ui is centered, so the corners show the background
+    let background = image.pixel(0, 0);
+    assert_eq!(image.pixel(image.width - 1, image.height - 1), background);
+
+    let drawn = (0..image.height)
+        .flat_map(|y| (0..image.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| image.pixel(x, y) != background)
+        .count();
+
+    assert!(drawn > 0, "the snapshot only shows the background");
+}
+
+#[test]
+fn snapshot_shows_params() {
+    let quiet = Snapshot::new()
+        .with_param("gain", 0.0)
+        .render::<gain::GainPlugin>()
+        .unwrap();
+
+    let loud = Snapshot::new()
+        .with_param("gain", 10.0)
+        .render::<gain::GainPlugin>()
+        .unwrap();
+
+    assert_ne!(Image::decode(&quiet).pixels, Image::decode(&loud).pixels);
+}
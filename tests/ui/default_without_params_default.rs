@@ -0,0 +1,9 @@
+use ori_vst::{Bool, Params};
+
+#[derive(Params)]
+struct BypassParams {
+    #[param(default = true, bypass)]
+    enabled: Bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ori_vst::{Float, Params};
+
+#[derive(Params)]
+struct GainParams {
+    #[param(name = "Gain", range = 0.0..=2.0)]
+    gain: Float,
+}
+
+fn main() {}